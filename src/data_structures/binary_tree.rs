@@ -1,37 +1,257 @@
+use super::render;
+
 pub struct BinaryTree<T> where T: PartialEq, T: PartialOrd {
-    root: Node<T>,
+    root: Option<Box<Node<T>>>,
 }
 
 impl<T: PartialOrd> BinaryTree<T> {
     pub fn new(value: T) -> Self {
-        Self { root: Node::new(value) }
+        Self { root: Some(Box::new(Node::new(value))) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
     }
 
     pub fn insert(&mut self, value: T) {
-        if value < *self.root.value {
-            self.root.insert_left(value);
-        } else {
-            self.root.insert_right(value);
+        let mut link = &mut self.root;
+
+        while let Some(node) = link {
+            if value < *node.value {
+                link = &mut node.left;
+            } else {
+                link = &mut node.right;
+            }
         }
+
+        *link = Some(Box::new(Node::new(value)));
     }
 
     pub fn size(&self) -> usize {
-        let mut result = 0;
+        self.iter().count()
+    }
 
-        self.root.count(&mut result);
+    pub fn contains(&self, value: &T) -> bool {
+        let mut current = self.root.as_ref();
 
-        result
+        while let Some(node) = current {
+            if *value < *node.value {
+                current = node.left.as_ref();
+            } else if *node.value < *value {
+                current = node.right.as_ref();
+            } else {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns an iterator over the values of the tree in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+
+    /// Returns the values that are in `self` or in `other`.
+    ///
+    /// Duplicates are treated as a multiset: a value present `m` times in `self`
+    /// and `n` times in `other` is present `max(m, n)` times in the result.
+    pub fn union(self, other: Self) -> Self {
+        self.merge(other, true, true, true)
+    }
+
+    /// Returns the values that are both in `self` and in `other`.
+    ///
+    /// A value present `m` times in `self` and `n` times in `other` is
+    /// present `min(m, n)` times in the result.
+    pub fn intersection(self, other: Self) -> Self {
+        self.merge(other, false, false, true)
+    }
+
+    /// Returns the values that are in `self` but not in `other`.
+    ///
+    /// A value present `m` times in `self` and `n` times in `other` is
+    /// present `m - n` times in the result (or not at all if `n >= m`).
+    pub fn difference(self, other: Self) -> Self {
+        self.merge(other, true, false, false)
+    }
+
+    /// Returns the values that are either in `self` or in `other`, but not in both.
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        self.merge(other, true, true, false)
+    }
+
+    /// Returns `true` if every value of `self` is also in `other`,
+    /// with at least the same number of occurrences.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let mut left = self.iter().peekable();
+        let mut right = other.iter().peekable();
+
+        while let Some(a) = left.peek() {
+            match right.peek() {
+                None => return false,
+                Some(b) => {
+                    if a < b {
+                        return false;
+                    } else if b < a {
+                        right.next();
+                    } else {
+                        left.next();
+                        right.next();
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Merges the in-order sequences of both trees in linear time, keeping the values
+    /// that are only in `self`, only in `other`, or in both, according to the flags.
+    /// The resulting tree is balanced.
+    fn merge(self, other: Self, only_left: bool, only_right: bool, both: bool) -> Self {
+        let mut left = Vec::from(self).into_iter().peekable();
+        let mut right = Vec::from(other).into_iter().peekable();
+        let mut result = Vec::new();
+
+        loop {
+            match (left.peek(), right.peek()) {
+                (Some(a), Some(b)) => {
+                    if a < b {
+                        let value = left.next().unwrap();
+                        if only_left {
+                            result.push(value);
+                        }
+                    } else if b < a {
+                        let value = right.next().unwrap();
+                        if only_right {
+                            result.push(value);
+                        }
+                    } else {
+                        let value = left.next().unwrap();
+                        right.next();
+                        if both {
+                            result.push(value);
+                        }
+                    }
+                }
+                (Some(_), None) => {
+                    if only_left {
+                        result.extend(left);
+                    }
+                    break;
+                }
+                (None, Some(_)) => {
+                    if only_right {
+                        result.extend(right);
+                    }
+                    break;
+                }
+                (None, None) => break,
+            }
+        }
+
+        Self::from_sorted(result)
+    }
+
+    /// Renders the tree as a Graphviz DOT digraph, using `label` to display the values.
+    /// Edges are labelled `L` or `R` depending on the side of the child.
+    pub fn to_dot(&self, label: impl Fn(&T) -> String) -> String {
+        render::dot(self.root.as_deref(), Node::labelled_children, |n| label(&n.value))
+    }
+
+    /// Renders the tree as an indented ASCII diagram, using `label` to display the values.
+    /// Children are prefixed with `L` or `R` depending on their side.
+    pub fn to_ascii(&self, label: impl Fn(&T) -> String) -> String {
+        render::ascii(self.root.as_deref(), Node::labelled_children, |n| label(&n.value))
+    }
+
+    /// Builds a balanced tree from values that are already in ascending order.
+    fn from_sorted(values: Vec<T>) -> Self {
+        let len = values.len();
+        Self { root: Node::build(&mut values.into_iter(), len) }
+    }
+}
+
+impl<T: PartialOrd> Default for BinaryTree<T> {
+    fn default() -> Self {
+        Self { root: None }
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for BinaryTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::default();
+        tree.extend(iter);
+        tree
     }
 }
 
-impl<T> Into<Vec<T>> for BinaryTree<T> where T: PartialEq, T: PartialOrd  {
-    fn into(self) -> Vec<T> {
-        let mut result = Vec::with_capacity(self.size());
-        self.root.populate(&mut result);
+impl<T: PartialOrd> Extend<T> for BinaryTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> From<BinaryTree<T>> for Vec<T> where T: PartialEq, T: PartialOrd {
+    /// Returns the values of the tree in ascending order.
+    fn from(mut tree: BinaryTree<T>) -> Self {
+        let mut result = Vec::with_capacity(tree.size());
+        if let Some(root) = tree.root.take() {
+            root.populate(&mut result);
+        }
         result
     }
 }
 
+/// Unlinks the nodes one by one, as the default drop would recurse once per level.
+impl<T> Drop for BinaryTree<T> where T: PartialEq, T: PartialOrd {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+pub struct Iter<'a, T> where T: PartialEq, T: PartialOrd {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> where T: PartialEq, T: PartialOrd {
+    fn push_left(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> where T: PartialEq, T: PartialOrd {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BinaryTree<T> where T: PartialEq, T: PartialOrd {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 struct Node<T> where T: PartialEq, T: PartialOrd {
     pub value: Box<T>,
     left: Option<Box<Node<T>>>,
@@ -45,16 +265,27 @@ impl<T> PartialEq for Node<T> where T: PartialEq, T: PartialOrd {
 }
 
 impl<T> PartialOrd for Node<T> where T: PartialEq, T: PartialOrd {
+    /// Compares the values in pre-order, an absent child being less than a present one.
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.value.partial_cmp(&other.value) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.left.partial_cmp(&other.left) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
+        let mut stack = vec![(Some(self), Some(other))];
+
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (None, None) => {}
+                (None, Some(_)) => return Some(core::cmp::Ordering::Less),
+                (Some(_), None) => return Some(core::cmp::Ordering::Greater),
+                (Some(a), Some(b)) => {
+                    match a.value.partial_cmp(&b.value) {
+                        Some(core::cmp::Ordering::Equal) => {}
+                        ord => return ord,
+                    }
+                    stack.push((a.right.as_deref(), b.right.as_deref()));
+                    stack.push((a.left.as_deref(), b.left.as_deref()));
+                }
+            }
         }
-        self.right.partial_cmp(&other.right)
+
+        Some(core::cmp::Ordering::Equal)
     }
 }
 
@@ -63,54 +294,42 @@ impl<T: PartialOrd> Node<T> {
         Self { value: Box::new(value), left: None, right: None }
     }
 
-    pub fn count(&self, result: &mut usize) {
-        *result += 1;
-
-        if let Some(left) = &self.left {
-            left.count(result)
+    /// Builds a balanced subtree from the next `len` values of the ascending sequence.
+    fn build(values: &mut impl Iterator<Item = T>, len: usize) -> Option<Box<Node<T>>> {
+        if len == 0 {
+            return None;
         }
-        if let Some(right) = &self.right {
-            right.count(result)
-        }
-    }
 
-    pub fn populate(self, vec: &mut Vec<T>) {
-        vec.push(*self.value);
+        let left = Node::build(values, len / 2);
+        let value = values.next()?;
+        let right = Node::build(values, len - len / 2 - 1);
 
-        if let Some(left) = self.left {
-            left.populate(vec)
-        }
-        if let Some(right) = self.right {
-            right.populate(vec)
-        }
+        Some(Box::new(Node { value: Box::new(value), left, right }))
     }
 
-    pub fn insert_left(&mut self, value: T) {
-        match &mut self.left {
-            Some(node) => {
-                if value < *node.value {
-                    node.insert_left(value)
-                } else {
-                    node.insert_right(value)
-                }
-            },
-            None => {
-                self.left = Some(Box::new(Node::new(value)))
-            }
-        }
+    fn labelled_children(&self) -> Vec<(Option<&'static str>, &Node<T>)> {
+        let left = self.left.as_deref().map(|n| (Some("L"), n));
+        let right = self.right.as_deref().map(|n| (Some("R"), n));
+        left.into_iter().chain(right).collect()
     }
 
-    pub fn insert_right(&mut self, value: T) {
-        match &mut self.right {
-            Some(node) => {
-                if value < *node.value {
-                    node.insert_left(value)
-                } else {
-                    node.insert_right(value)
+    /// Moves the values of the subtree into `vec`, in ascending order.
+    pub fn populate(self: Box<Self>, vec: &mut Vec<T>) {
+        let mut stack = Vec::new();
+        let mut current = Some(self);
+
+        loop {
+            while let Some(mut node) = current {
+                current = node.left.take();
+                stack.push(node);
+            }
+
+            match stack.pop() {
+                None => break,
+                Some(mut node) => {
+                    current = node.right.take();
+                    vec.push(*node.value);
                 }
-            },
-            None => {
-                self.right = Some(Box::new(Node::new(value)))
             }
         }
     }
@@ -118,7 +337,18 @@ impl<T: PartialOrd> Node<T> {
 
 #[cfg(test)]
 mod test {
-    use super::BinaryTree;
+    use super::{BinaryTree, Node};
+
+    fn height<T: PartialOrd>(node: &Option<Box<Node<T>>>) -> usize {
+        match node {
+            None => 0,
+            Some(n) => 1 + height(&n.left).max(height(&n.right)),
+        }
+    }
+
+    fn sorted(tree: BinaryTree<i32>) -> Vec<i32> {
+        tree.into()
+    }
 
     #[test]
     fn new() {
@@ -129,6 +359,45 @@ mod test {
         assert_eq!(asvec[0], 0);
     }
 
+    #[test]
+    fn default() {
+        let tree: BinaryTree<i32> = BinaryTree::default();
+        assert!(tree.is_empty());
+        assert_eq!(tree.size(), 0);
+        let asvec: Vec<i32> = tree.into();
+        assert!(asvec.is_empty());
+    }
+
+    #[test]
+    fn insert_into_empty() {
+        let mut tree = BinaryTree::default();
+        tree.insert(3);
+        tree.insert(1);
+
+        assert!(!tree.is_empty());
+        assert_eq!(tree.size(), 2);
+        let asvec: Vec<i32> = tree.into();
+        assert_eq!(asvec, vec![1, 3]);
+    }
+
+    #[test]
+    fn from_iter() {
+        let tree: BinaryTree<i32> = vec![5, 3, 8, 1, 4].into_iter().collect();
+        assert_eq!(tree.size(), 5);
+        let asvec: Vec<i32> = tree.into();
+        assert_eq!(asvec, vec![1, 3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut tree = BinaryTree::new(2);
+        tree.extend(vec![7, 0]);
+        tree.extend(Vec::new());
+        assert_eq!(tree.size(), 3);
+        let asvec: Vec<i32> = tree.into();
+        assert_eq!(asvec, vec![0, 2, 7]);
+    }
+
     #[test]
     fn feature() {
         let mut tree = BinaryTree::new(0);
@@ -152,4 +421,201 @@ mod test {
         assert_eq!(asvec[5], 2);
         assert_eq!(asvec[6], 9);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn contains() {
+        let tree: BinaryTree<i32> = vec![5, 3, 8, 1].into_iter().collect();
+
+        assert!(tree.contains(&1));
+        assert!(tree.contains(&8));
+        assert!(!tree.contains(&2));
+        assert!(!BinaryTree::default().contains(&2));
+    }
+
+    #[test]
+    fn iter() {
+        let tree: BinaryTree<i32> = vec![5, 3, 8, 1, 5].into_iter().collect();
+        let values: Vec<&i32> = tree.iter().collect();
+
+        assert_eq!(values, vec![&1, &3, &5, &5, &8]);
+        assert_eq!((&tree).into_iter().count(), 5);
+        assert_eq!(BinaryTree::<i32>::default().iter().next(), None);
+    }
+
+    #[test]
+    fn union() {
+        let a: BinaryTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+        let b: BinaryTree<i32> = vec![2, 3, 4, 8].into_iter().collect();
+
+        assert_eq!(sorted(a.union(b)), vec![1, 2, 3, 4, 5, 7, 8]);
+    }
+
+    #[test]
+    fn intersection() {
+        let a: BinaryTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+        let b: BinaryTree<i32> = vec![2, 3, 4, 7].into_iter().collect();
+
+        assert_eq!(sorted(a.intersection(b)), vec![3, 7]);
+    }
+
+    #[test]
+    fn difference() {
+        let a: BinaryTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+        let b: BinaryTree<i32> = vec![2, 3, 4, 7].into_iter().collect();
+
+        assert_eq!(sorted(a.difference(b)), vec![1, 5]);
+    }
+
+    #[test]
+    fn symmetric_difference() {
+        let a: BinaryTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+        let b: BinaryTree<i32> = vec![2, 3, 4, 7].into_iter().collect();
+
+        assert_eq!(sorted(a.symmetric_difference(b)), vec![1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn set_operations_with_empty() {
+        let a: BinaryTree<i32> = vec![1, 2].into_iter().collect();
+
+        assert_eq!(sorted(BinaryTree::new(1).union(BinaryTree::default())), vec![1]);
+        assert_eq!(sorted(BinaryTree::default().union(BinaryTree::new(1))), vec![1]);
+        assert!(a.intersection(BinaryTree::default()).is_empty());
+    }
+
+    #[test]
+    fn set_operations_with_duplicates() {
+        let a = || -> BinaryTree<i32> { vec![1, 1, 1, 2].into_iter().collect() };
+        let b = || -> BinaryTree<i32> { vec![1, 2, 2].into_iter().collect() };
+
+        assert_eq!(sorted(a().union(b())), vec![1, 1, 1, 2, 2]);
+        assert_eq!(sorted(a().intersection(b())), vec![1, 2]);
+        assert_eq!(sorted(a().difference(b())), vec![1, 1]);
+        assert_eq!(sorted(a().symmetric_difference(b())), vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn set_operations_are_balanced() {
+        // Inserting sorted values degenerates into a list...
+        let a: BinaryTree<i32> = (0..1000).collect();
+        let b: BinaryTree<i32> = (500..1500).collect();
+        assert_eq!(height(&a.root), 1000);
+
+        // ...but the result of a set operation is rebuilt balanced.
+        let union = a.union(b);
+        assert_eq!(union.size(), 1500);
+        assert_eq!(height(&union.root), 11);
+    }
+
+    #[test]
+    fn is_subset() {
+        let a: BinaryTree<i32> = vec![3, 7].into_iter().collect();
+        let b: BinaryTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+        let c: BinaryTree<i32> = vec![3, 3].into_iter().collect();
+
+        assert!(a.is_subset(&b));
+        assert!(!b.is_subset(&a));
+        assert!(a.is_subset(&a));
+        assert!(!c.is_subset(&b));
+        assert!(BinaryTree::default().is_subset(&a));
+        assert!(!a.is_subset(&BinaryTree::default()));
+    }
+
+    #[test]
+    fn to_dot() {
+        let tree: BinaryTree<i32> = vec![2, 1, 3].into_iter().collect();
+
+        let expected = "digraph {
+    n0 [label=\"2\"];
+    n1 [label=\"1\"];
+    n0 -> n1 [label=\"L\"];
+    n2 [label=\"3\"];
+    n0 -> n2 [label=\"R\"];
+}
+";
+        assert_eq!(tree.to_dot(|v| v.to_string()), expected);
+    }
+
+    #[test]
+    fn to_ascii() {
+        let tree: BinaryTree<i32> = vec![5, 3, 8, 4, 9].into_iter().collect();
+
+        let expected = "\
+5
+├── L: 3
+│   └── R: 4
+└── R: 8
+    └── R: 9
+";
+        assert_eq!(tree.to_ascii(|v| v.to_string()), expected);
+        assert_eq!(BinaryTree::<i32>::default().to_ascii(|v| v.to_string()), "");
+    }
+
+    const STRESS: i32 = 1_000_000;
+
+    /// Builds a degenerate tree where every node only has a right child,
+    /// as if the values had been inserted in ascending order.
+    fn degenerate() -> BinaryTree<i32> {
+        let mut root = None;
+        for value in (0..STRESS).rev() {
+            root = Some(Box::new(Node { value: Box::new(value), left: None, right: root }));
+        }
+        BinaryTree { root }
+    }
+
+    #[test]
+    fn stress_size_and_contains() {
+        let tree = degenerate();
+
+        assert_eq!(tree.size(), STRESS as usize);
+        assert!(tree.contains(&(STRESS - 1)));
+        assert!(!tree.contains(&STRESS));
+        assert_eq!(tree.iter().last(), Some(&(STRESS - 1)));
+    }
+
+    #[test]
+    fn stress_insert() {
+        let mut tree = degenerate();
+        tree.insert(STRESS);
+        tree.insert(-1);
+
+        assert_eq!(tree.size(), STRESS as usize + 2);
+    }
+
+    #[test]
+    fn stress_into_vec() {
+        let asvec: Vec<i32> = degenerate().into();
+
+        assert_eq!(asvec.len(), STRESS as usize);
+        assert!(asvec.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn stress_set_operations() {
+        let evens = BinaryTree::from_sorted((0..STRESS / 2).map(|v| v * 2).collect());
+        let union = degenerate().union(evens);
+
+        assert_eq!(union.size(), STRESS as usize);
+        assert!(degenerate().is_subset(&union));
+    }
+
+    #[test]
+    fn stress_compare() {
+        let a = degenerate();
+        let b = degenerate();
+
+        assert_eq!(a.root.partial_cmp(&b.root), Some(std::cmp::Ordering::Equal));
+    }
+
+    #[test]
+    fn stress_render() {
+        let dot = degenerate().to_dot(|v| v.to_string());
+
+        assert!(dot.ends_with("n999999 [label=\"999999\"];\n    n999998 -> n999999 [label=\"R\"];\n}\n"));
+    }
+
+    #[test]
+    fn stress_drop() {
+        drop(degenerate());
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::traits::fold::Fold;
+use crate::traits::functor::Functor;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+/// An immutable singly linked list whose tails are shared between lists.
+///
+/// `cons` creates a new list that points to the existing one instead of copying it,
+/// so any number of lists can share a common suffix. Cloning a list is O(1).
+pub struct ConsList<T> {
+    head: Link<T>,
+    len: usize,
+}
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+impl<T> ConsList<T> {
+    /// Returns the empty list.
+    pub fn new() -> Self {
+        Self { head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns a new list made of `value` followed by this list, in O(1).
+    pub fn cons(&self, value: T) -> Self {
+        let node = Node { value, next: self.head.clone() };
+        Self { head: Some(Rc::new(node)), len: self.len + 1 }
+    }
+
+    /// Returns the first value of the list.
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Returns the list without its first value, sharing its nodes with this list.
+    /// Returns `None` if the list is empty.
+    pub fn tail(&self) -> Option<Self> {
+        self.head.as_ref().map(|node| Self { head: node.next.clone(), len: self.len - 1 })
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { current: self.head.as_deref() }
+    }
+}
+
+impl<T> Clone for ConsList<T> {
+    fn clone(&self) -> Self {
+        Self { head: self.head.clone(), len: self.len }
+    }
+}
+
+impl<T> Default for ConsList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stops at the first node still shared with another list.
+impl<T> Drop for ConsList<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+
+        while let Some(node) = link {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => link = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for ConsList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Debug> Debug for ConsList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The list keeps the order of the iterator: the first value becomes the head.
+impl<T> FromIterator<T> for ConsList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        values.into_iter().rev().fold(Self::new(), |list, value| list.cons(value))
+    }
+}
+
+pub struct Iter<'a, T> {
+    current: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.current?;
+        self.current = node.next.as_deref();
+        Some(&node.value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ConsList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Functor<T> for ConsList<T> {
+    type Functor<U> = ConsList<U>;
+
+    fn fmap<U>(&self, f: impl Fn(&T) -> U) -> Self::Functor<U> {
+        self.iter().map(f).collect()
+    }
+}
+
+impl<T> Fold<T> for ConsList<T> {
+    fn fold(&self, start: T, f: fn(&T, &T) -> T) -> T {
+        let mut accum = start;
+
+        for value in self {
+            accum = f(&accum, value);
+        }
+
+        accum
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::traits::fold::Fold;
+    use crate::traits::functor::Functor;
+
+    use super::ConsList;
+
+    #[test]
+    fn empty() {
+        let list: ConsList<i32> = ConsList::default();
+
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.head(), None);
+        assert!(list.tail().is_none());
+    }
+
+    #[test]
+    fn cons_head_tail() {
+        let list = ConsList::new().cons(3).cons(2).cons(1);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.head(), Some(&1));
+
+        let tail = list.tail().unwrap();
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail.head(), Some(&2));
+
+        let last = tail.tail().unwrap();
+        assert_eq!(last.head(), Some(&3));
+        assert!(last.tail().unwrap().is_empty());
+    }
+
+    #[test]
+    fn shared_tails() {
+        let shared: ConsList<i32> = (3..=5).collect();
+        let a = shared.cons(2).cons(1);
+        let b = shared.cons(0);
+
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![0, 3, 4, 5]);
+
+        let a_suffix = a.tail().unwrap().tail().unwrap();
+        let b_suffix = b.tail().unwrap();
+        assert!(Rc::ptr_eq(a_suffix.head.as_ref().unwrap(), b_suffix.head.as_ref().unwrap()));
+
+        // Dropping a list keeps the nodes that are still shared.
+        drop(a);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![0, 3, 4, 5]);
+        assert_eq!(shared.len(), 3);
+    }
+
+    #[test]
+    fn equality() {
+        let a: ConsList<i32> = (1..=3).collect();
+        let b = ConsList::new().cons(3).cons(2).cons(1);
+
+        assert_eq!(a, b);
+        assert_ne!(a, b.tail().unwrap());
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn fmap() {
+        let list: ConsList<i32> = (1..=4).collect();
+        let mapped = list.fmap(|x| x % 2 == 0);
+
+        assert_eq!(mapped, vec![false, true, false, true].into_iter().collect());
+        assert!(ConsList::<i32>::new().fmap(|x| x * 2).is_empty());
+    }
+
+    #[test]
+    fn fold() {
+        let list: ConsList<i32> = (1..=4).collect();
+
+        assert_eq!(list.fold(0, |a, b| a + b), 10);
+        assert_eq!(list.fold(1, |a, b| a * b), 24);
+        assert_eq!(ConsList::new().fold(321, |a, b| a + b), 321);
+    }
+
+    #[test]
+    fn stress_drop() {
+        let list: ConsList<usize> = (0..1_000_000).collect();
+        let shared = list.tail().unwrap();
+
+        drop(list);
+        assert_eq!(shared.len(), 999_999);
+        drop(shared);
+    }
+}
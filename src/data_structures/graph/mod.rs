@@ -0,0 +1,249 @@
+use std::fmt::{self, Debug};
+
+pub use shortest_path::ShortestPaths;
+pub use traversal::{Bfs, Dfs, SortError};
+
+mod shortest_path;
+mod spanning_tree;
+mod traversal;
+
+/// The index of a node in a [`Graph`], returned by [`Graph::add_node`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// The index of an edge in a [`Graph`], returned by [`Graph::add_edge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl EdgeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A graph with values of type `N` on its nodes and of type `E` on its edges, stored as adjacency lists.
+///
+/// Nodes and edges are never removed, so their ids are plain indices that stay valid for the life
+/// of the graph. [`node`](Self::node), [`edge`](Self::edge) and their `_mut` versions return `None`
+/// for an id that does not belong to the graph, as `slice::get` does, and the other methods taking
+/// a node id panic, as slice indexing does.
+///
+/// In an undirected graph, every edge is listed in the adjacency of both of its ends, so the algorithms
+/// meant for directed graphs see it as a pair of opposite edges.
+#[derive(Clone)]
+pub struct Graph<N, E> {
+    nodes: Vec<N>,
+    edges: Vec<Edge<E>>,
+    /// The neighbors of every node, along with the edge that leads to them, in order of insertion.
+    adjacency: Vec<Vec<(NodeId, EdgeId)>>,
+    directed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edge<E> {
+    source: NodeId,
+    target: NodeId,
+    weight: E,
+}
+
+impl<E> Edge<E> {
+    pub fn source(&self) -> NodeId {
+        self.source
+    }
+
+    pub fn target(&self) -> NodeId {
+        self.target
+    }
+
+    pub fn weight(&self) -> &E {
+        &self.weight
+    }
+
+    pub fn weight_mut(&mut self) -> &mut E {
+        &mut self.weight
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn directed() -> Self {
+        Self { nodes: Vec::new(), edges: Vec::new(), adjacency: Vec::new(), directed: true }
+    }
+
+    pub fn undirected() -> Self {
+        Self { directed: false, ..Self::directed() }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn add_node(&mut self, value: N) -> NodeId {
+        self.nodes.push(value);
+        self.adjacency.push(Vec::new());
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Adds an edge from `source` to `target`, or between them if the graph is undirected.
+    /// Several edges may link the same nodes.
+    pub fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> EdgeId {
+        self.check(source);
+        self.check(target);
+
+        let id = EdgeId(self.edges.len());
+        self.adjacency[source.0].push((target, id));
+        if !self.directed && source != target {
+            self.adjacency[target.0].push((source, id));
+        }
+        self.edges.push(Edge { source, target, weight });
+
+        id
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&N> {
+        self.nodes.get(id.0)
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut N> {
+        self.nodes.get_mut(id.0)
+    }
+
+    pub fn edge(&self, id: EdgeId) -> Option<&Edge<E>> {
+        self.edges.get(id.0)
+    }
+
+    pub fn edge_mut(&mut self, id: EdgeId) -> Option<&mut Edge<E>> {
+        self.edges.get_mut(id.0)
+    }
+
+    /// Returns the first edge added from `source` to `target`, or between them if the graph is undirected.
+    pub fn find_edge(&self, source: NodeId, target: NodeId) -> Option<EdgeId> {
+        self.check(source);
+        self.check(target);
+        self.adjacency[source.0].iter()
+            .find(|(neighbor, _)| *neighbor == target)
+            .map(|(_, edge)| *edge)
+    }
+
+    /// Returns an iterator over the ids and values of the nodes, in order of insertion.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().enumerate().map(|(i, value)| (NodeId(i), value))
+    }
+
+    /// Returns an iterator over the ids of the edges and the edges, in order of insertion.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, &Edge<E>)> {
+        self.edges.iter().enumerate().map(|(i, edge)| (EdgeId(i), edge))
+    }
+
+    /// Returns an iterator over the nodes reachable from `id` through a single edge, in order of insertion
+    /// of the edges. A node linked by several edges is returned once per edge.
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.check(id);
+        self.adjacency[id.0].iter().map(|(neighbor, _)| *neighbor)
+    }
+
+    /// Returns an iterator over the edges leaving `id`, along with the node at their other end.
+    pub fn edges_from(&self, id: NodeId) -> impl Iterator<Item = (NodeId, &Edge<E>)> {
+        self.check(id);
+        self.adjacency[id.0].iter().map(|(neighbor, edge)| (*neighbor, &self.edges[edge.0]))
+    }
+
+    /// Panics if `id` is not a node of the graph.
+    fn check(&self, id: NodeId) {
+        assert!(id.0 < self.nodes.len(), "{:?} is not a node of the graph", id);
+    }
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self::directed()
+    }
+}
+
+impl<N: Debug, E: Debug> Debug for Graph<N, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("directed", &self.directed)
+            .field("nodes", &self.nodes)
+            .field("edges", &self.edges)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Graph, NodeId};
+
+    #[test]
+    fn directed() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        let ab = graph.add_edge(a, b, 1);
+        graph.add_edge(a, c, 2);
+        graph.add_edge(c, a, 3);
+
+        assert!(graph.is_directed());
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b, c]);
+        assert_eq!(graph.neighbors(b).count(), 0);
+        assert_eq!(graph.find_edge(a, b), Some(ab));
+        assert_eq!(graph.find_edge(b, a), None);
+
+        let edge = graph.edge(ab).unwrap();
+        assert_eq!((edge.source(), edge.target(), *edge.weight()), (a, b, 1));
+    }
+
+    #[test]
+    fn undirected() {
+        let mut graph = Graph::undirected();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let ab = graph.add_edge(a, b, ());
+        graph.add_edge(b, b, ());
+
+        assert!(!graph.is_directed());
+        assert_eq!(graph.find_edge(b, a), Some(ab));
+        assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b]);
+        // A loop is only listed once.
+        assert_eq!(graph.neighbors(b).collect::<Vec<_>>(), vec![a, b]);
+    }
+
+    #[test]
+    fn values() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        let edge = graph.add_edge(a, b, "ab".to_owned());
+
+        *graph.node_mut(b).unwrap() += 10;
+        graph.edge_mut(edge).unwrap().weight_mut().push('!');
+
+        assert_eq!(graph.nodes().map(|(_, v)| *v).collect::<Vec<_>>(), vec![1, 12]);
+        assert_eq!(graph.edges_from(a).map(|(n, e)| (n, e.weight().as_str())).collect::<Vec<_>>(), vec![(b, "ab!")]);
+        assert_eq!(graph.node(NodeId(2)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a node of the graph")]
+    fn invalid_node() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node(());
+        graph.add_edge(a, NodeId(1), ());
+    }
+}
@@ -0,0 +1,237 @@
+use std::ops::Add;
+
+use crate::data_structures::heap::IndexedPriorityQueue;
+
+use super::{Graph, NodeId};
+
+/// The shortest paths from a start node to every node it reaches, as returned by [`Graph::dijkstra`].
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<W> {
+    start: NodeId,
+    distances: Vec<Option<W>>,
+    /// The node before each node on its shortest path.
+    previous: Vec<Option<NodeId>>,
+}
+
+impl<W: Copy> ShortestPaths<W> {
+    pub fn start(&self) -> NodeId {
+        self.start
+    }
+
+    /// Returns the cost of the shortest path to `target`, or `None` if it cannot be reached.
+    pub fn distance(&self, target: NodeId) -> Option<W> {
+        self.distances.get(target.0).copied().flatten()
+    }
+
+    /// Returns the nodes of the shortest path to `target`, from the start to `target` included.
+    pub fn path(&self, target: NodeId) -> Option<Vec<NodeId>> {
+        self.distance(target)?;
+        Some(follow(&self.previous, target))
+    }
+}
+
+/// Rebuilds the path to `target` by following the previous nodes back to the start.
+fn follow(previous: &[Option<NodeId>], target: NodeId) -> Vec<NodeId> {
+    let mut path = vec![target];
+    while let Some(node) = previous[path[path.len() - 1].0] {
+        path.push(node);
+    }
+
+    path.reverse();
+    path
+}
+
+impl<N, E> Graph<N, E> {
+    /// Computes the shortest paths from `start` to every node, with Dijkstra's algorithm.
+    ///
+    /// The cost of every edge is given by `cost`, and must not be negative: `W::default()` is the cost
+    /// of an empty path. Costs have to be totally ordered, so floats need to be wrapped in a type that is.
+    pub fn dijkstra<W>(&self, start: NodeId, mut cost: impl FnMut(&E) -> W) -> ShortestPaths<W>
+    where
+        W: Ord + Copy + Add<Output = W> + Default,
+    {
+        self.check(start);
+
+        let mut distances = vec![None; self.node_count()];
+        let mut previous = vec![None; self.node_count()];
+        let mut queue = IndexedPriorityQueue::min();
+        queue.push(start, W::default());
+        distances[start.0] = Some(W::default());
+
+        // The priority of a node decreases whenever a shorter path is found, and it is final once popped.
+        while let Some((id, distance)) = queue.pop() {
+            for (neighbor, edge) in self.edges_from(id) {
+                let candidate = distance + cost(&edge.weight);
+                if distances[neighbor.0].is_none_or(|known| candidate < known) {
+                    distances[neighbor.0] = Some(candidate);
+                    previous[neighbor.0] = Some(id);
+                    queue.push(neighbor, candidate);
+                }
+            }
+        }
+
+        ShortestPaths { start, distances, previous }
+    }
+
+    /// Returns the cost and the nodes of a shortest path from `start` to `goal` with the A* algorithm,
+    /// or `None` if `goal` cannot be reached.
+    ///
+    /// `heuristic` estimates the cost from a node to `goal`. The path is the shortest as long as the estimate
+    /// never exceeds the actual cost and, for every edge, does not drop by more than the cost of the edge.
+    /// With a heuristic that is always `W::default()`, this is Dijkstra's algorithm stopping at `goal`.
+    pub fn astar<W>(
+        &self,
+        start: NodeId,
+        goal: NodeId,
+        mut cost: impl FnMut(&E) -> W,
+        mut heuristic: impl FnMut(NodeId) -> W,
+    ) -> Option<(W, Vec<NodeId>)>
+    where
+        W: Ord + Copy + Add<Output = W> + Default,
+    {
+        self.check(start);
+        self.check(goal);
+
+        let mut distances = vec![None; self.node_count()];
+        let mut previous = vec![None; self.node_count()];
+        // Ordered by estimated total cost, then by the cost so far.
+        let mut queue = IndexedPriorityQueue::min();
+        queue.push(start, (heuristic(start), W::default()));
+        distances[start.0] = Some(W::default());
+
+        while let Some((id, (_, distance))) = queue.pop() {
+            if id == goal {
+                return Some((distance, follow(&previous, goal)));
+            }
+
+            for (neighbor, edge) in self.edges_from(id) {
+                let candidate = distance + cost(&edge.weight);
+                if distances[neighbor.0].is_none_or(|known| candidate < known) {
+                    distances[neighbor.0] = Some(candidate);
+                    previous[neighbor.0] = Some(id);
+                    queue.push(neighbor, (candidate + heuristic(neighbor), candidate));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Graph, NodeId};
+
+    fn indices(path: Vec<NodeId>) -> Vec<usize> {
+        path.into_iter().map(NodeId::index).collect()
+    }
+
+    /// A directed graph with nodes 0 to 5.
+    fn roads() -> Graph<&'static str, u32> {
+        let mut graph = Graph::directed();
+        let ids: Vec<NodeId> = ["a", "b", "c", "d", "e", "f"].into_iter().map(|name| graph.add_node(name)).collect();
+        for (source, target, cost) in [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15), (2, 3, 11), (2, 5, 2), (3, 4, 6)] {
+            graph.add_edge(ids[source], ids[target], cost);
+        }
+        graph
+    }
+
+    #[test]
+    fn dijkstra() {
+        let graph = roads();
+        let paths = graph.dijkstra(NodeId(0), |cost| *cost);
+
+        assert_eq!(paths.start(), NodeId(0));
+        let distances: Vec<Option<u32>> = (0..6).map(|i| paths.distance(NodeId(i))).collect();
+        assert_eq!(distances, vec![Some(0), Some(7), Some(9), Some(20), Some(26), Some(11)]);
+        assert_eq!(paths.path(NodeId(4)).map(indices), Some(vec![0, 2, 3, 4]));
+        assert_eq!(paths.path(NodeId(0)).map(indices), Some(vec![0]));
+
+        let paths = graph.dijkstra(NodeId(3), |cost| *cost);
+        assert_eq!(paths.distance(NodeId(0)), None);
+        assert_eq!(paths.path(NodeId(0)), None);
+    }
+
+    #[test]
+    fn dijkstra_undirected() {
+        let graph = roads();
+        let mut undirected = Graph::undirected();
+        for (_, name) in graph.nodes() {
+            undirected.add_node(*name);
+        }
+        for (_, edge) in graph.edges() {
+            undirected.add_edge(edge.source(), edge.target(), *edge.weight());
+        }
+
+        let paths = undirected.dijkstra(NodeId(4), |cost| *cost);
+        assert_eq!(paths.distance(NodeId(5)), Some(19));
+        assert_eq!(paths.path(NodeId(5)).map(indices), Some(vec![4, 3, 2, 5]));
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        // A 10 × 10 grid with a wall in column 5, open at the bottom row.
+        const SIZE: usize = 10;
+        let mut graph = Graph::undirected();
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                graph.add_node((x, y));
+            }
+        }
+        let id = |x: usize, y: usize| NodeId(y * SIZE + x);
+        let wall = |x: usize, y: usize| x == 5 && y < SIZE - 1;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                if x + 1 < SIZE && !wall(x, y) && !wall(x + 1, y) {
+                    graph.add_edge(id(x, y), id(x + 1, y), 1);
+                }
+                if y + 1 < SIZE && !wall(x, y) && !wall(x, y + 1) {
+                    graph.add_edge(id(x, y), id(x, y + 1), 1);
+                }
+            }
+        }
+
+        let goal = id(9, 0);
+        let manhattan = |node: NodeId| {
+            let (x, y) = graph.node(node).unwrap();
+            (x.abs_diff(9) + y.abs_diff(0)) as u32
+        };
+        let (cost, path) = graph.astar(id(0, 0), goal, |cost| *cost, manhattan).unwrap();
+
+        assert_eq!(cost, 9 + 2 * 9);
+        assert_eq!(path.len(), cost as usize + 1);
+        assert_eq!(path.first(), Some(&id(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(Some(cost), graph.dijkstra(id(0, 0), |cost| *cost).distance(goal));
+
+        assert_eq!(graph.astar(id(0, 0), id(5, 0), |cost| *cost, |_| 0), None);
+        assert_eq!(graph.astar(goal, goal, |cost| *cost, |_| 0), Some((0, vec![goal])));
+    }
+
+    #[test]
+    fn stress_long_path() {
+        const STRESS: usize = 1_000_000;
+
+        let mut graph = Graph::directed();
+        let mut previous = graph.add_node(());
+        for _ in 1..STRESS {
+            let node = graph.add_node(());
+            graph.add_edge(previous, node, 2u64);
+            previous = node;
+        }
+
+        let paths = graph.dijkstra(NodeId(0), |cost| *cost);
+        assert_eq!(paths.distance(previous), Some(2 * (STRESS as u64 - 1)));
+        assert_eq!(paths.path(previous).unwrap().len(), STRESS);
+
+        let (cost, path) = graph.astar(NodeId(0), previous, |cost| *cost, |_| 0).unwrap();
+        assert_eq!(cost, 2 * (STRESS as u64 - 1));
+        assert_eq!(path.len(), STRESS);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a node of the graph")]
+    fn astar_invalid_goal() {
+        roads().astar(NodeId(0), NodeId(6), |cost| *cost, |_| 0);
+    }
+}
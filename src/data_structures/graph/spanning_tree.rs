@@ -0,0 +1,129 @@
+use super::{EdgeId, Graph};
+
+impl<N, E> Graph<N, E> {
+    /// Returns the edges of a minimum spanning forest, with Kruskal's algorithm: a tree of least total cost
+    /// linking the nodes of every connected component, with one edge less than the nodes of the component.
+    ///
+    /// The direction of the edges is ignored. Edges of equal cost are taken in order of insertion,
+    /// and the edges are returned in increasing order of cost.
+    pub fn minimum_spanning_tree<W: Ord>(&self, mut cost: impl FnMut(&E) -> W) -> Vec<EdgeId> {
+        let mut edges: Vec<(W, EdgeId)> = self.edges().map(|(id, edge)| (cost(&edge.weight), id)).collect();
+        edges.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut components = DisjointSets::new(self.node_count());
+        let mut tree = Vec::with_capacity(self.node_count().saturating_sub(1));
+        for (_, id) in edges {
+            let edge = &self.edges[id.0];
+            if components.union(edge.source.0, edge.target.0) {
+                tree.push(id);
+            }
+        }
+
+        tree
+    }
+}
+
+/// A union-find over the indices of the nodes.
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect(), size: vec![1; len] }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            // Path halving: every other node on the way points to its grandparent.
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Merges the sets of `a` and `b`, returning `false` if they already were the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Graph, NodeId};
+
+    #[test]
+    fn minimum_spanning_tree() {
+        //     7     8
+        //  a --- b --- c
+        //  |5  / 9|  7/ 5\
+        //  d --- e --- f  |
+        //     15  \ 8 /9  |
+        //          g -----
+        let mut graph = Graph::undirected();
+        let ids: Vec<NodeId> = "abcdefg".chars().map(|name| graph.add_node(name)).collect();
+        let edges = [
+            (0, 1, 7), (0, 3, 5), (1, 2, 8), (1, 3, 9), (1, 4, 7), (2, 4, 5),
+            (3, 4, 15), (3, 5, 6), (4, 5, 8), (4, 6, 9), (5, 6, 11),
+        ];
+        for (source, target, cost) in edges {
+            graph.add_edge(ids[source], ids[target], cost);
+        }
+
+        let tree = graph.minimum_spanning_tree(|cost| *cost);
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.iter().map(|id| graph.edge(*id).unwrap().weight()).sum::<i32>(), 39);
+
+        let chosen: Vec<(usize, usize)> = tree.iter()
+            .map(|id| graph.edge(*id).unwrap())
+            .map(|edge| (edge.source().index(), edge.target().index()))
+            .collect();
+        assert_eq!(chosen, vec![(0, 3), (2, 4), (3, 5), (0, 1), (1, 4), (4, 6)]);
+    }
+
+    #[test]
+    fn spanning_forest() {
+        let mut graph = Graph::directed();
+        let ids: Vec<NodeId> = (0..5).map(|i| graph.add_node(i)).collect();
+        graph.add_edge(ids[1], ids[0], 3);
+        graph.add_edge(ids[0], ids[1], 1);
+        graph.add_edge(ids[2], ids[2], 0);
+        graph.add_edge(ids[3], ids[4], 2);
+
+        let tree = graph.minimum_spanning_tree(|cost| *cost);
+        let costs: Vec<i32> = tree.iter().map(|id| *graph.edge(*id).unwrap().weight()).collect();
+        assert_eq!(costs, vec![1, 2]);
+
+        assert!(Graph::<(), ()>::undirected().minimum_spanning_tree(|_| 0).is_empty());
+    }
+
+    #[test]
+    fn stress_minimum_spanning_tree() {
+        const STRESS: usize = 1_000_000;
+
+        // A path, with shortcuts that cost more than the path they skip.
+        let mut graph = Graph::undirected();
+        let ids: Vec<NodeId> = (0..STRESS).map(|i| graph.add_node(i)).collect();
+        for i in 1..STRESS {
+            graph.add_edge(ids[i - 1], ids[i], 1);
+            if i >= 2 {
+                graph.add_edge(ids[i - 2], ids[i], 3);
+            }
+        }
+
+        let tree = graph.minimum_spanning_tree(|cost| *cost);
+        assert_eq!(tree.len(), STRESS - 1);
+        assert!(tree.iter().all(|id| *graph.edge(*id).unwrap().weight() == 1));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use super::{Graph, NodeId};
+
+/// A breadth-first traversal of the nodes reachable from a start node, as returned by [`Graph::bfs`].
+pub struct Bfs<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    queue: VecDeque<NodeId>,
+    visited: Vec<bool>,
+}
+
+/// A depth-first traversal of the nodes reachable from a start node in pre-order, as returned by [`Graph::dfs`].
+pub struct Dfs<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    stack: Vec<NodeId>,
+    visited: Vec<bool>,
+}
+
+impl<N, E> Iterator for Bfs<'_, N, E> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.queue.pop_front()?;
+        for neighbor in self.graph.neighbors(id) {
+            if !self.visited[neighbor.0] {
+                self.visited[neighbor.0] = true;
+                self.queue.push_back(neighbor);
+            }
+        }
+
+        Some(id)
+    }
+}
+
+impl<N, E> Iterator for Dfs<'_, N, E> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        loop {
+            let id = self.stack.pop()?;
+            if self.visited[id.0] {
+                continue;
+            }
+            self.visited[id.0] = true;
+
+            // Pushed in reverse, so that the neighbors are visited in order of insertion of the edges.
+            let neighbors = self.graph.adjacency[id.0].iter().rev().map(|(neighbor, _)| *neighbor);
+            self.stack.extend(neighbors.filter(|neighbor| !self.visited[neighbor.0]));
+            return Some(id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortError {
+    /// The graph has a cycle, which goes through these nodes in order and back to the first one.
+    Cycle(Vec<NodeId>),
+}
+
+impl Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortError::Cycle(nodes) => write!(f, "the graph has a cycle through {:?}", nodes),
+        }
+    }
+}
+
+impl Error for SortError {}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mark {
+    New,
+    /// On the path of the ongoing depth-first search.
+    Open,
+    Done,
+}
+
+impl<N, E> Graph<N, E> {
+    /// Returns a breadth-first traversal of the nodes reachable from `start`, `start` included.
+    pub fn bfs(&self, start: NodeId) -> Bfs<'_, N, E> {
+        self.check(start);
+        let mut visited = vec![false; self.node_count()];
+        visited[start.0] = true;
+        Bfs { graph: self, queue: VecDeque::from([start]), visited }
+    }
+
+    /// Returns a depth-first traversal of the nodes reachable from `start`, `start` included,
+    /// each node coming before the nodes discovered from it.
+    pub fn dfs(&self, start: NodeId) -> Dfs<'_, N, E> {
+        self.check(start);
+        Dfs { graph: self, stack: vec![start], visited: vec![false; self.node_count()] }
+    }
+
+    /// Returns the nodes ordered so that every edge goes from a node to a later one,
+    /// or one of the cycles that make it impossible.
+    ///
+    /// Nodes that are not ordered by the edges keep their order of insertion as far as possible.
+    /// An undirected graph can only be sorted if it has no edges, as any edge is a cycle of two nodes.
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, SortError> {
+        let mut marks = vec![Mark::New; self.node_count()];
+        let mut order = Vec::with_capacity(self.node_count());
+        // The path of the search, with the position of the next neighbor to visit for each node.
+        let mut path: Vec<(NodeId, usize)> = Vec::new();
+
+        // The order is the reverse of the post-order, so the searches start from the last nodes.
+        for root in (0..self.node_count()).rev() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+            marks[root] = Mark::Open;
+            path.push((NodeId(root), 0));
+
+            while let Some((id, next)) = path.last_mut() {
+                let Some(&(neighbor, _)) = self.adjacency[id.0].get(*next) else {
+                    marks[id.0] = Mark::Done;
+                    order.push(*id);
+                    path.pop();
+                    continue;
+                };
+                *next += 1;
+
+                match marks[neighbor.0] {
+                    Mark::New => {
+                        marks[neighbor.0] = Mark::Open;
+                        path.push((neighbor, 0));
+                    }
+                    Mark::Open => {
+                        let start = path.iter().position(|(id, _)| *id == neighbor).expect("open nodes are on the path");
+                        return Err(SortError::Cycle(path[start..].iter().map(|(id, _)| *id).collect()));
+                    }
+                    Mark::Done => {}
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Returns the strongly connected components: the largest sets of nodes that can all reach each other.
+    ///
+    /// Components are listed in reverse topological order, every edge between two components going
+    /// from a later one to an earlier one. In an undirected graph, they are the connected components.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        // Tarjan's algorithm, with the recursion replaced by an explicit path as in the topological sort.
+        let mut tarjan = Tarjan {
+            index: vec![None; self.node_count()],
+            low: vec![0; self.node_count()],
+            on_stack: vec![false; self.node_count()],
+            stack: Vec::new(),
+            counter: 0,
+        };
+        let mut path: Vec<(NodeId, usize)> = Vec::new();
+        let mut components = Vec::new();
+
+        for root in 0..self.node_count() {
+            if tarjan.index[root].is_some() {
+                continue;
+            }
+            tarjan.open(NodeId(root));
+            path.push((NodeId(root), 0));
+
+            while let Some((id, next)) = path.last_mut() {
+                let id = *id;
+                if let Some(&(neighbor, _)) = self.adjacency[id.0].get(*next) {
+                    *next += 1;
+                    match tarjan.index[neighbor.0] {
+                        None => {
+                            tarjan.open(neighbor);
+                            path.push((neighbor, 0));
+                        }
+                        Some(index) if tarjan.on_stack[neighbor.0] => tarjan.low[id.0] = tarjan.low[id.0].min(index),
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                path.pop();
+                if let Some((parent, _)) = path.last() {
+                    tarjan.low[parent.0] = tarjan.low[parent.0].min(tarjan.low[id.0]);
+                }
+                if Some(tarjan.low[id.0]) == tarjan.index[id.0] {
+                    components.push(tarjan.close(id));
+                }
+            }
+        }
+
+        components
+    }
+}
+
+struct Tarjan {
+    /// The order in which the nodes were reached.
+    index: Vec<Option<usize>>,
+    /// The lowest index reachable from each node through the nodes still on the stack.
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<NodeId>,
+    counter: usize,
+}
+
+impl Tarjan {
+    fn open(&mut self, id: NodeId) {
+        self.index[id.0] = Some(self.counter);
+        self.low[id.0] = self.counter;
+        self.counter += 1;
+        self.stack.push(id);
+        self.on_stack[id.0] = true;
+    }
+
+    /// Pops the component whose first reached node is `root`.
+    fn close(&mut self, root: NodeId) -> Vec<NodeId> {
+        let mut component = Vec::new();
+        while let Some(member) = self.stack.pop() {
+            self.on_stack[member.0] = false;
+            component.push(member);
+            if member == root {
+                break;
+            }
+        }
+
+        component.reverse();
+        component
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Graph, NodeId};
+    use super::SortError;
+
+    /// A directed graph with nodes 0 to `count - 1` and the given edges.
+    fn graph(count: usize, edges: &[(usize, usize)]) -> Graph<(), ()> {
+        let mut graph = Graph::directed();
+        for _ in 0..count {
+            graph.add_node(());
+        }
+        for &(source, target) in edges {
+            graph.add_edge(NodeId(source), NodeId(target), ());
+        }
+        graph
+    }
+
+    fn indices(ids: impl IntoIterator<Item = NodeId>) -> Vec<usize> {
+        ids.into_iter().map(NodeId::index).collect()
+    }
+
+    #[test]
+    fn traversals() {
+        //   0 → 1 → 3
+        //   ↓   ↓
+        //   2 → 4    5
+        let graph = graph(6, &[(0, 1), (0, 2), (1, 3), (1, 4), (2, 4)]);
+
+        assert_eq!(indices(graph.bfs(NodeId(0))), vec![0, 1, 2, 3, 4]);
+        assert_eq!(indices(graph.dfs(NodeId(0))), vec![0, 1, 3, 4, 2]);
+        assert_eq!(indices(graph.dfs(NodeId(2))), vec![2, 4]);
+        assert_eq!(indices(graph.bfs(NodeId(5))), vec![5]);
+    }
+
+    #[test]
+    fn traversals_with_cycles() {
+        let graph = graph(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]);
+
+        assert_eq!(indices(graph.bfs(NodeId(1))), vec![1, 2, 0, 3]);
+        assert_eq!(indices(graph.dfs(NodeId(1))), vec![1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn topological_sort() {
+        let graph = graph(6, &[(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+        let order = graph.topological_sort().unwrap();
+
+        assert_eq!(order.len(), 6);
+        let position = |id: usize| order.iter().position(|node| node.index() == id).unwrap();
+        for (_, edge) in graph.edges() {
+            assert!(position(edge.source().index()) < position(edge.target().index()));
+        }
+
+        assert_eq!(Graph::<(), ()>::directed().topological_sort(), Ok(vec![]));
+    }
+
+    #[test]
+    fn cycles() {
+        let graph = graph(5, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+        assert_eq!(graph.topological_sort(), Err(SortError::Cycle(vec![NodeId(3), NodeId(1), NodeId(2)])));
+
+        let graph = self::graph(2, &[(0, 1), (1, 1)]);
+        assert_eq!(graph.topological_sort(), Err(SortError::Cycle(vec![NodeId(1)])));
+
+        let mut graph = Graph::undirected();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        assert_eq!(graph.topological_sort(), Ok(vec![a, b]));
+        graph.add_edge(a, b, ());
+        assert_eq!(graph.topological_sort(), Err(SortError::Cycle(vec![b, a])));
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        // {0, 1, 2} → {3, 4} → {5}, and {6} on its own.
+        let graph = graph(7, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5), (1, 5)]);
+
+        let components: Vec<Vec<usize>> = graph.strongly_connected_components().into_iter().map(indices).collect();
+        assert_eq!(components, vec![vec![5], vec![3, 4], vec![0, 1, 2], vec![6]]);
+    }
+
+    #[test]
+    fn connected_components() {
+        let mut graph = Graph::undirected();
+        let ids: Vec<NodeId> = (0..5).map(|_| graph.add_node(())).collect();
+        graph.add_edge(ids[0], ids[2], ());
+        graph.add_edge(ids[3], ids[2], ());
+        graph.add_edge(ids[1], ids[4], ());
+
+        let mut components: Vec<Vec<usize>> = graph.strongly_connected_components().into_iter().map(indices).collect();
+        for component in &mut components {
+            component.sort();
+        }
+        assert_eq!(components, vec![vec![0, 2, 3], vec![1, 4]]);
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    #[test]
+    fn stress_long_path() {
+        let edges: Vec<(usize, usize)> = (1..STRESS).map(|i| (i - 1, i)).collect();
+        let mut graph = graph(STRESS, &edges);
+
+        assert_eq!(graph.bfs(NodeId(0)).count(), STRESS);
+        assert!(indices(graph.dfs(NodeId(0))).into_iter().eq(0..STRESS));
+        assert!(indices(graph.topological_sort().unwrap()).into_iter().eq(0..STRESS));
+        assert_eq!(graph.strongly_connected_components().len(), STRESS);
+
+        graph.add_edge(NodeId(STRESS - 1), NodeId(0), ());
+        match graph.topological_sort() {
+            Err(SortError::Cycle(cycle)) => assert_eq!(cycle.len(), STRESS),
+            Ok(_) => panic!("the graph has a cycle"),
+        }
+        assert_eq!(graph.strongly_connected_components().len(), 1);
+    }
+}
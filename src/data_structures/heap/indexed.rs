@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+/// A priority queue of keys, each with a priority that can be changed or removed by key.
+///
+/// It is a binary heap like [`Heap`](super::Heap), along with the position of every key in the heap,
+/// so that `push`, `pop`, `change_priority` and `remove` are all O(log n). Every key is in the queue
+/// at most once.
+#[derive(Clone)]
+pub struct IndexedPriorityQueue<K, P> {
+    items: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+    /// Whether the highest priority comes first.
+    max: bool,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> IndexedPriorityQueue<K, P> {
+    /// Returns an empty queue that pops the key with the lowest priority first.
+    pub fn min() -> Self {
+        Self { items: Vec::new(), positions: HashMap::new(), max: false }
+    }
+
+    /// Returns an empty queue that pops the key with the highest priority first.
+    pub fn max() -> Self {
+        Self { max: true, ..Self::min() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&index| &self.items[index].1)
+    }
+
+    /// Adds the key with the priority, or changes its priority if it already is in the queue,
+    /// and returns the previous priority if there was one.
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if self.contains(&key) {
+            return self.change_priority(&key, priority);
+        }
+
+        self.positions.insert(key.clone(), self.items.len());
+        self.items.push((key, priority));
+        self.sift_up(self.items.len() - 1);
+        None
+    }
+
+    /// Removes and returns the first key along with its priority.
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.items.is_empty() {
+            return None;
+        }
+        Some(self.take(0))
+    }
+
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.items.first().map(|(key, priority)| (key, priority))
+    }
+
+    /// Changes the priority of the key and returns the previous one, or returns `None`
+    /// and leaves the queue untouched if the key is not in the queue.
+    pub fn change_priority(&mut self, key: &K, priority: P) -> Option<P> {
+        let index = *self.positions.get(key)?;
+        let previous = std::mem::replace(&mut self.items[index].1, priority);
+
+        let index = self.sift_up(index);
+        self.sift_down(index);
+        Some(previous)
+    }
+
+    /// Removes the key and returns its priority.
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let index = *self.positions.get(key)?;
+        Some(self.take(index).1)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.positions.clear();
+    }
+
+    /// Returns an iterator over the keys and their priorities, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.items.iter().map(|(key, priority)| (key, priority))
+    }
+
+    /// Removes the item at `index`, filling the hole with the last item.
+    fn take(&mut self, index: usize) -> (K, P) {
+        let last = self.items.len() - 1;
+        self.swap(index, last);
+        let (key, priority) = self.items.pop().expect("the queue is not empty");
+        self.positions.remove(&key);
+
+        if index < self.items.len() {
+            let index = self.sift_up(index);
+            self.sift_down(index);
+        }
+        (key, priority)
+    }
+
+    /// Whether the item at `a` has to come before the item at `b`.
+    fn before(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.items[a].1, &self.items[b].1);
+        if self.max { a > b } else { a < b }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.items.swap(a, b);
+        *self.positions.get_mut(&self.items[a].0).expect("every key has a position") = a;
+        *self.positions.get_mut(&self.items[b].0).expect("every key has a position") = b;
+    }
+
+    /// Moves the item at `index` up to its place, and returns its new position.
+    fn sift_up(&mut self, mut index: usize) -> usize {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.before(index, parent) {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+        index
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut first = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.items.len() && self.before(child, first) {
+                    first = child;
+                }
+            }
+            if first == index {
+                break;
+            }
+            self.swap(index, first);
+            index = first;
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Default for IndexedPriorityQueue<K, P> {
+    fn default() -> Self {
+        Self::min()
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Extend<(K, P)> for IndexedPriorityQueue<K, P> {
+    fn extend<I: IntoIterator<Item = (K, P)>>(&mut self, iter: I) {
+        for (key, priority) in iter {
+            self.push(key, priority);
+        }
+    }
+}
+
+impl<K: Debug, P: Debug> Debug for IndexedPriorityQueue<K, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.items.iter().map(|(key, priority)| (key, priority))).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexedPriorityQueue;
+
+    fn drain<K: std::hash::Hash + Eq + Clone, P: Ord>(mut queue: IndexedPriorityQueue<K, P>) -> Vec<(K, P)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut queue = IndexedPriorityQueue::min();
+        assert_eq!(queue.pop(), None);
+
+        queue.extend([("a", 5), ("b", 2), ("c", 8), ("d", 1)]);
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.peek(), Some((&"d", &1)));
+        assert_eq!(queue.priority(&"c"), Some(&8));
+
+        // Pushing a key again changes its priority.
+        assert_eq!(queue.push("c", 0), Some(8));
+        assert_eq!(queue.len(), 4);
+        assert_eq!(drain(queue), vec![("c", 0), ("d", 1), ("b", 2), ("a", 5)]);
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut queue = IndexedPriorityQueue::max();
+        queue.extend((0..10).map(|i| (i, i)));
+
+        assert_eq!(queue.change_priority(&3, 20), Some(3));
+        assert_eq!(queue.change_priority(&9, -1), Some(9));
+        assert_eq!(queue.change_priority(&10, 0), None);
+        assert!(!queue.contains(&10));
+
+        let keys: Vec<i32> = drain(queue).into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![3, 8, 7, 6, 5, 4, 2, 1, 0, 9]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut queue = IndexedPriorityQueue::min();
+        queue.extend((0..10).map(|i| (i.to_string(), i)));
+
+        assert_eq!(queue.remove(&"4".to_owned()), Some(4));
+        assert_eq!(queue.remove(&"0".to_owned()), Some(0));
+        assert_eq!(queue.remove(&"9".to_owned()), Some(9));
+        assert_eq!(queue.remove(&"4".to_owned()), None);
+        assert_eq!(queue.iter().count(), 7);
+
+        let priorities: Vec<i32> = drain(queue).into_iter().map(|(_, priority)| priority).collect();
+        assert_eq!(priorities, vec![1, 2, 3, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn stress_decrease_key() {
+        const STRESS: usize = 50_000;
+
+        let mut queue = IndexedPriorityQueue::min();
+        queue.extend((0..STRESS).map(|key| (key, STRESS + key)));
+        // Reverse the order of the even keys, and drop the odd ones.
+        for key in (0..STRESS).step_by(2) {
+            queue.change_priority(&key, STRESS - key);
+            queue.remove(&(key + 1));
+        }
+
+        assert_eq!(queue.len(), STRESS / 2);
+        let keys = drain(queue).into_iter().map(|(key, _)| key);
+        assert!(keys.eq((0..STRESS).step_by(2).rev()));
+    }
+}
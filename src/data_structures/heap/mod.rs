@@ -0,0 +1,170 @@
+use std::fmt::{self, Debug};
+
+pub use indexed::IndexedPriorityQueue;
+
+mod indexed;
+
+/// A binary heap that pops either its smallest or its largest element first.
+///
+/// Elements are kept in a vector, each element coming before its children at `2i + 1` and `2i + 2`,
+/// so that `push` and `pop` are O(log n) and `peek` is O(1).
+#[derive(Clone)]
+pub struct Heap<T: Ord> {
+    items: Vec<T>,
+    /// Whether the largest element comes first.
+    max: bool,
+}
+
+impl<T: Ord> Heap<T> {
+    /// Returns an empty heap that pops its smallest element first.
+    pub fn min() -> Self {
+        Self { items: Vec::new(), max: false }
+    }
+
+    /// Returns an empty heap that pops its largest element first.
+    pub fn max() -> Self {
+        Self { items: Vec::new(), max: true }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.sift_up(self.items.len() - 1);
+    }
+
+    /// Removes and returns the first element: the smallest one, or the largest one for a max heap.
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.items.pop()?;
+        if self.items.is_empty() {
+            return Some(last);
+        }
+
+        let first = std::mem::replace(&mut self.items[0], last);
+        self.sift_down(0);
+        Some(first)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Returns an iterator over the elements, in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Returns the elements, in no particular order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Returns the elements in the order they would be popped.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    /// Whether `a` has to come before `b`.
+    fn before(&self, a: &T, b: &T) -> bool {
+        if self.max { a > b } else { a < b }
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.before(&self.items[index], &self.items[parent]) {
+                break;
+            }
+            self.items.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut first = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.items.len() && self.before(&self.items[child], &self.items[first]) {
+                    first = child;
+                }
+            }
+            if first == index {
+                break;
+            }
+            self.items.swap(index, first);
+            index = first;
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for Heap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: Ord + Debug> Debug for Heap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.items).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Heap;
+
+    #[test]
+    fn min() {
+        let mut heap = Heap::min();
+        assert_eq!(heap.pop(), None);
+        assert_eq!(heap.peek(), None);
+
+        heap.extend([5, 3, 8, 1, 9, 3]);
+        assert_eq!(heap.len(), 6);
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(3));
+
+        heap.push(2);
+        assert_eq!(heap.into_sorted_vec(), vec![2, 3, 5, 8, 9]);
+    }
+
+    #[test]
+    fn max() {
+        let mut heap = Heap::max();
+        heap.extend(["b", "d", "a", "c"]);
+
+        assert_eq!(heap.peek(), Some(&"d"));
+        assert_eq!(heap.iter().count(), 4);
+        assert_eq!(heap.clone().into_sorted_vec(), vec!["d", "c", "b", "a"]);
+
+        heap.clear();
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn stress_heap_sort() {
+        const STRESS: usize = 1_000_000;
+
+        let mut heap = Heap::min();
+        // A permutation of 0..STRESS, as 7919 is prime with STRESS.
+        heap.extend((0..STRESS).map(|i| i * 7919 % STRESS));
+        assert!(heap.into_sorted_vec().into_iter().eq(0..STRESS));
+    }
+}
@@ -0,0 +1,415 @@
+use std::ops::Range;
+
+/// A balanced binary search tree of half-open intervals `[start, end)`,
+/// answering "which intervals overlap this range / contain this point" queries.
+///
+/// Intervals are ordered by their start, and each node is augmented with the
+/// greatest end of its subtree so that queries skip the subtrees that cannot
+/// contain a match.
+pub struct IntervalTree<T> where T: PartialOrd, T: Clone {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+}
+
+impl<T> IntervalTree<T> where T: PartialOrd, T: Clone {
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn insert(&mut self, interval: Range<T>) {
+        self.root = Some(Node::insert(self.root.take(), interval));
+        self.len += 1;
+    }
+
+    /// Removes one occurrence of the interval, returning `true` if it was present.
+    pub fn remove(&mut self, interval: &Range<T>) -> bool {
+        let mut removed = false;
+        self.root = Node::remove(self.root.take(), interval, &mut removed);
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    pub fn contains(&self, interval: &Range<T>) -> bool {
+        let mut current = self.root.as_ref();
+
+        while let Some(node) = current {
+            if less(interval, &node.interval) {
+                current = node.left.as_ref();
+            } else if less(&node.interval, interval) {
+                current = node.right.as_ref();
+            } else {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns an iterator over the intervals, ordered by start then end.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self, Query::All)
+    }
+
+    /// Returns an iterator over the intervals that overlap `range`, ordered by start.
+    ///
+    /// Skipping subtrees by their greatest end still walks the intervals that start before `range` but end
+    /// before it too, so each match may take O(log n) steps to reach: iterating over `k` matches takes
+    /// O(min(n, (k + 1) log n)) time, not the O(log n + k) of a centered interval tree.
+    pub fn overlapping(&self, range: &Range<T>) -> Iter<'_, T> {
+        Iter::new(self, Query::Range(range.clone()))
+    }
+
+    /// Returns an iterator over the intervals that contain `point`, ordered by start.
+    ///
+    /// As with [`overlapping`](Self::overlapping), iterating over `k` matches takes O(min(n, (k + 1) log n)) time.
+    pub fn stabbing(&self, point: &T) -> Iter<'_, T> {
+        Iter::new(self, Query::Point(point.clone()))
+    }
+}
+
+impl<T> Default for IntervalTree<T> where T: PartialOrd, T: Clone {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<Range<T>> for IntervalTree<T> where T: PartialOrd, T: Clone {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T> Extend<Range<T>> for IntervalTree<T> where T: PartialOrd, T: Clone {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+/// Orders intervals by start, then by end.
+fn less<T: PartialOrd>(a: &Range<T>, b: &Range<T>) -> bool {
+    a.start < b.start || (a.start == b.start && a.end < b.end)
+}
+
+enum Query<T> {
+    All,
+    Range(Range<T>),
+    Point(T),
+}
+
+impl<T: PartialOrd> Query<T> {
+    /// Returns `true` if a subtree whose intervals all end before `max_end` may contain a match.
+    fn may_match_subtree(&self, max_end: &T) -> bool {
+        match self {
+            Query::All => true,
+            Query::Range(range) => range.start < *max_end,
+            Query::Point(point) => point < max_end,
+        }
+    }
+
+    /// Returns `true` if an interval starting at `start` (or later) may still match.
+    fn may_match_start(&self, start: &T) -> bool {
+        match self {
+            Query::All => true,
+            Query::Range(range) => *start < range.end,
+            Query::Point(point) => start <= point,
+        }
+    }
+
+    fn matches(&self, interval: &Range<T>) -> bool {
+        match self {
+            Query::All => true,
+            Query::Range(range) => interval.start < range.end && range.start < interval.end,
+            Query::Point(point) => interval.start <= *point && *point < interval.end,
+        }
+    }
+}
+
+/// An in-order iterator over the intervals of an [`IntervalTree`] that match a query.
+pub struct Iter<'a, T> where T: PartialOrd, T: Clone {
+    stack: Vec<&'a Node<T>>,
+    query: Query<T>,
+}
+
+impl<'a, T> Iter<'a, T> where T: PartialOrd, T: Clone {
+    fn new(tree: &'a IntervalTree<T>, query: Query<T>) -> Self {
+        let mut iter = Self { stack: Vec::new(), query };
+        iter.push_left(tree.root.as_deref());
+        iter
+    }
+
+    fn push_left(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(current) = node {
+            if !self.query.may_match_subtree(&current.max_end) {
+                break;
+            }
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> where T: PartialOrd, T: Clone {
+    type Item = &'a Range<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            if !self.query.may_match_start(&node.interval.start) {
+                // Every remaining interval starts at or after this one.
+                self.stack.clear();
+                return None;
+            }
+
+            self.push_left(node.right.as_deref());
+
+            if self.query.matches(&node.interval) {
+                return Some(&node.interval);
+            }
+        }
+
+        None
+    }
+}
+
+struct Node<T> where T: PartialOrd, T: Clone {
+    interval: Range<T>,
+    max_end: T,
+    height: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+fn height<T: PartialOrd + Clone>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.height)
+}
+
+impl<T> Node<T> where T: PartialOrd, T: Clone {
+    fn new(interval: Range<T>) -> Self {
+        let max_end = interval.end.clone();
+        Self { interval, max_end, height: 1, left: None, right: None }
+    }
+
+    /// Recomputes the height and the greatest end from the children.
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+
+        let mut max_end = &self.interval.end;
+        for child in [&self.left, &self.right].into_iter().flatten() {
+            if *max_end < child.max_end {
+                max_end = &child.max_end;
+            }
+        }
+        self.max_end = max_end.clone();
+    }
+
+    fn rotate_right(mut node: Box<Self>) -> Box<Self> {
+        let mut left = node.left.take().unwrap();
+        node.left = left.right.take();
+        node.update();
+        left.right = Some(node);
+        left.update();
+        left
+    }
+
+    fn rotate_left(mut node: Box<Self>) -> Box<Self> {
+        let mut right = node.right.take().unwrap();
+        node.right = right.left.take();
+        node.update();
+        right.left = Some(node);
+        right.update();
+        right
+    }
+
+    fn balance(mut node: Box<Self>) -> Box<Self> {
+        node.update();
+
+        let (hl, hr) = (height(&node.left), height(&node.right));
+
+        if hl > hr + 1 {
+            let left = node.left.as_ref().unwrap();
+            if height(&left.left) < height(&left.right) {
+                node.left = Some(Node::rotate_left(node.left.take().unwrap()));
+            }
+            Node::rotate_right(node)
+        } else if hr > hl + 1 {
+            let right = node.right.as_ref().unwrap();
+            if height(&right.right) < height(&right.left) {
+                node.right = Some(Node::rotate_right(node.right.take().unwrap()));
+            }
+            Node::rotate_left(node)
+        } else {
+            node
+        }
+    }
+
+    fn insert(node: Option<Box<Self>>, interval: Range<T>) -> Box<Self> {
+        match node {
+            None => Box::new(Node::new(interval)),
+            Some(mut node) => {
+                if less(&interval, &node.interval) {
+                    node.left = Some(Node::insert(node.left.take(), interval));
+                } else {
+                    node.right = Some(Node::insert(node.right.take(), interval));
+                }
+                Node::balance(node)
+            }
+        }
+    }
+
+    fn remove(node: Option<Box<Self>>, interval: &Range<T>, removed: &mut bool) -> Option<Box<Self>> {
+        let mut node = node?;
+
+        if less(interval, &node.interval) {
+            node.left = Node::remove(node.left.take(), interval, removed);
+        } else if less(&node.interval, interval) {
+            node.right = Node::remove(node.right.take(), interval, removed);
+        } else {
+            *removed = true;
+            match (node.left.take(), node.right.take()) {
+                (None, right) => return right,
+                (left, None) => return left,
+                (left, Some(right)) => {
+                    let (mut min, right) = Node::remove_min(right);
+                    min.left = left;
+                    min.right = right;
+                    return Some(Node::balance(min));
+                }
+            }
+        }
+
+        Some(Node::balance(node))
+    }
+
+    /// Detaches the node with the smallest interval, returning it and the rest of the subtree.
+    fn remove_min(mut node: Box<Self>) -> (Box<Self>, Option<Box<Self>>) {
+        match node.left.take() {
+            None => {
+                let right = node.right.take();
+                (node, right)
+            }
+            Some(left) => {
+                let (min, left) = Node::remove_min(left);
+                node.left = left;
+                (min, Some(Node::balance(node)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::Range;
+
+    use super::{height, IntervalTree};
+
+    fn collect<'a>(iter: impl Iterator<Item = &'a Range<i32>>) -> Vec<Range<i32>> {
+        iter.cloned().collect()
+    }
+
+    fn schedule() -> IntervalTree<i32> {
+        vec![15..20, 10..30, 17..19, 5..20, 12..15, 30..40].into_iter().collect()
+    }
+
+    #[test]
+    fn empty() {
+        let tree: IntervalTree<i32> = IntervalTree::default();
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.overlapping(&(0..10)).next(), None);
+        assert_eq!(tree.stabbing(&0).next(), None);
+    }
+
+    #[test]
+    fn iter_is_ordered() {
+        let tree = schedule();
+
+        assert_eq!(tree.len(), 6);
+        assert_eq!(collect(tree.iter()), vec![5..20, 10..30, 12..15, 15..20, 17..19, 30..40]);
+    }
+
+    #[test]
+    fn overlapping() {
+        let tree = schedule();
+
+        assert_eq!(collect(tree.overlapping(&(14..16))), vec![5..20, 10..30, 12..15, 15..20]);
+        assert_eq!(collect(tree.overlapping(&(29..31))), vec![10..30, 30..40]);
+        assert_eq!(collect(tree.overlapping(&(40..50))), vec![]);
+        assert_eq!(collect(tree.overlapping(&(0..5))), vec![]);
+    }
+
+    #[test]
+    fn stabbing() {
+        let tree = schedule();
+
+        assert_eq!(collect(tree.stabbing(&18)), vec![5..20, 10..30, 15..20, 17..19]);
+        // Intervals are half-open: the end is excluded.
+        assert_eq!(collect(tree.stabbing(&30)), vec![30..40]);
+        assert_eq!(collect(tree.stabbing(&4)), vec![]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut tree = schedule();
+
+        assert!(tree.remove(&(10..30)));
+        assert!(!tree.remove(&(10..30)));
+        assert!(!tree.remove(&(10..31)));
+        assert_eq!(tree.len(), 5);
+        assert!(!tree.contains(&(10..30)));
+        assert_eq!(collect(tree.stabbing(&25)), vec![]);
+        assert_eq!(collect(tree.stabbing(&18)), vec![5..20, 15..20, 17..19]);
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut tree: IntervalTree<i32> = vec![1..3, 1..3, 1..3].into_iter().collect();
+
+        assert_eq!(collect(tree.stabbing(&2)).len(), 3);
+        assert!(tree.remove(&(1..3)));
+        assert_eq!(collect(tree.stabbing(&2)), vec![1..3, 1..3]);
+    }
+
+    #[test]
+    fn balanced_against_brute_force() {
+        let intervals: Vec<Range<i32>> = (0..1000).map(|i| (i * 7) % 500..(i * 7) % 500 + i % 13 + 1).collect();
+        let mut tree: IntervalTree<i32> = intervals.iter().cloned().collect();
+
+        assert!(height(&tree.root) <= 15);
+
+        for i in (0..1000).step_by(3) {
+            assert!(tree.remove(&intervals[i]));
+        }
+
+        let remaining: Vec<&Range<i32>> = intervals.iter().enumerate()
+            .filter(|(i, _)| i % 3 != 0)
+            .map(|(_, r)| r)
+            .collect();
+
+        assert_eq!(tree.len(), remaining.len());
+        assert!(height(&tree.root) <= 15);
+
+        for query in [0..1, 100..110, 250..251, 499..600] {
+            let mut expected: Vec<Range<i32>> = remaining.iter()
+                .filter(|r| r.start < query.end && query.start < r.end)
+                .map(|r| (*r).clone())
+                .collect();
+            expected.sort_by_key(|r| (r.start, r.end));
+
+            assert_eq!(collect(tree.overlapping(&query)), expected);
+        }
+    }
+}
@@ -1,46 +1,380 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
 use std::ops::Deref;
+use std::ptr::NonNull;
 
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// A doubly linked list that keeps track of both ends and of its length,
+/// so that pushing and popping at either end are O(1).
 pub struct LinkedList<T> {
-    first: Option<Box<Node<T>>>
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<Box<Node<T>>>,
 }
 
+// SAFETY: the list owns its nodes just like a `Box` would, and no node is reachable from anywhere else:
+// moving the list to another thread only moves values of type `T`, which requires `T: Send`.
+unsafe impl<T> Send for LinkedList<T> where T: Send {}
+// SAFETY: a shared list only hands out shared references to its values, which requires `T: Sync`.
+unsafe impl<T> Sync for LinkedList<T> where T: Sync {}
+
 impl<T> LinkedList<T> {
     pub fn new() -> Self {
-        Self { first: None }
+        Self { head: None, tail: None, len: 0, marker: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
+    /// Appends a value at the back of the list. Same as [`LinkedList::push_back`].
     pub fn push(&mut self, value: T) {
-        match &mut self.first {
-            Some(node) => node.push(value),
-            None => self.first = Some(Box::new(Node::new(value)))
-        }
+        self.push_back(value)
     }
 
+    /// Removes the value at the back of the list. Same as [`LinkedList::pop_back`].
     pub fn pop(&mut self) -> Option<T> {
-        match &mut self.first {
-            None => None,
-            Some(node) => {
-                if node.has_next() {
-                    return node.pop();
-                } else {
-                    let v = self.first.take();
-                    return v.map(|x| { x.value });
+        self.pop_back()
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Node::alloc(value);
+
+        // SAFETY: `node` was just allocated and the tail, if any, is a live node owned by the list.
+        unsafe {
+            (*node.as_ptr()).prev = self.tail;
+            match self.tail {
+                None => self.head = Some(node),
+                Some(tail) => (*tail.as_ptr()).next = Some(node),
+            }
+        }
+
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Node::alloc(value);
+
+        // SAFETY: `node` was just allocated and the head, if any, is a live node owned by the list.
+        unsafe {
+            (*node.as_ptr()).next = self.head;
+            match self.head {
+                None => self.tail = Some(node),
+                Some(head) => (*head.as_ptr()).prev = Some(node),
+            }
+        }
+
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        // SAFETY: the tail belongs to this list.
+        self.tail.map(|tail| unsafe { self.unlink(tail).value })
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        // SAFETY: the head belongs to this list.
+        self.head.map(|head| unsafe { self.unlink(head).value })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        // SAFETY: the node is live as long as the list, and borrowing the list prevents any mutation.
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        // SAFETY: the node is live as long as the list, and borrowing the list prevents any mutation.
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live as long as the list, and borrowing the list mutably makes the reference unique.
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live as long as the list, and borrowing the list mutably makes the reference unique.
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Returns an iterator over references to the values, from front to back.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+    }
+
+    /// Returns an iterator over mutable references to the values, from front to back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+    }
+
+    /// Returns a cursor pointing at the front element, or at the "ghost" position if the list is empty.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let index = self.head.map(|_| 0);
+        CursorMut { current: self.head, index, list: self }
+    }
+
+    /// Returns a cursor pointing at the back element, or at the "ghost" position if the list is empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let index = self.tail.map(|_| self.len - 1);
+        CursorMut { current: self.tail, index, list: self }
+    }
+
+    /// Reverses the order of the elements in place, in O(n).
+    pub fn reverse(&mut self) {
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            // SAFETY: every node reached from the head is live, and only one is borrowed at a time.
+            unsafe {
+                let node = &mut *node.as_ptr();
+                std::mem::swap(&mut node.prev, &mut node.next);
+                current = node.prev;
+            }
+        }
+
+        std::mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Sorts the list in place, keeping the order of equal elements.
+    pub fn sort(&mut self) where T: Ord {
+        self.sort_by(T::cmp)
+    }
+
+    /// Sorts the list in place with a key extraction function, keeping the order of equal elements.
+    pub fn sort_by_key<K: Ord>(&mut self, mut key: impl FnMut(&T) -> K) {
+        self.sort_by(|a, b| key(a).cmp(&key(b)))
+    }
+
+    /// Sorts the list in place with a comparison function, keeping the order of equal elements.
+    ///
+    /// This is a bottom-up merge sort that relinks the existing nodes:
+    /// it runs in O(n log n) and does not allocate.
+    pub fn sort_by(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        if self.len < 2 {
+            return;
+        }
+
+        // While the nodes are being relinked, the list looks empty: if `compare`
+        // panics, the nodes are leaked instead of being left in an invalid list.
+        let len = std::mem::take(&mut self.len);
+        let mut chain = self.head.take();
+        self.tail = None;
+
+        let mut run = 1;
+        loop {
+            let mut rest = chain;
+            let mut head = None;
+            let mut tail: Link<T> = None;
+            let mut merges = 0;
+
+            while rest.is_some() {
+                // SAFETY: `rest` and the merged chains only hold nodes of this list, each reached from a single chain.
+                unsafe {
+                    let a = Self::take_run(&mut rest, run);
+                    let b = Self::take_run(&mut rest, run);
+                    let (merged_head, merged_tail) = Self::merge_chains(a, b, &mut compare);
+
+                    match tail {
+                        None => head = merged_head,
+                        Some(tail) => (*tail.as_ptr()).next = merged_head,
+                    }
+                    tail = merged_tail;
                 }
+                merges += 1;
             }
+
+            chain = head;
+            if merges <= 1 {
+                break;
+            }
+            run *= 2;
         }
+
+        self.head = chain;
+        self.len = len;
+        self.relink();
     }
 
-    pub fn len(&self) -> usize {
-        match &self.first {
-            None => 0,
-            Some(node) => node.len()
+    /// Moves all the elements of `other` into this list, both lists being already sorted.
+    /// The result is sorted, and equal elements of this list come before the ones of `other`.
+    /// Runs in O(n + m) without allocating.
+    pub fn merge(&mut self, mut other: LinkedList<T>) where T: Ord {
+        let len = std::mem::take(&mut self.len) + std::mem::take(&mut other.len);
+        let a = self.head.take();
+        let b = other.head.take();
+        self.tail = None;
+        other.tail = None;
+
+        // SAFETY: both chains are made of the live nodes of two distinct lists.
+        let (head, _) = unsafe { Self::merge_chains(a, b, &mut T::cmp) };
+
+        self.head = head;
+        self.len = len;
+        self.relink();
+    }
+
+    /// Removes consecutive equal elements, keeping the first one of each group.
+    pub fn dedup(&mut self) where T: PartialEq {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes consecutive elements for which `same_bucket(element, previous)` returns `true`,
+    /// `previous` being the last element that was kept.
+    pub fn dedup_by(&mut self, mut same_bucket: impl FnMut(&T, &T) -> bool) {
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            // SAFETY: `node` and the candidates following it all belong to this list,
+            // and `node` is never the one being unlinked.
+            unsafe {
+                let mut next = (*node.as_ptr()).next;
+
+                while let Some(candidate) = next {
+                    if !same_bucket(&(*candidate.as_ptr()).value, &(*node.as_ptr()).value) {
+                        break;
+                    }
+                    next = (*candidate.as_ptr()).next;
+                    drop(self.unlink(candidate));
+                }
+
+                current = next;
+            }
+        }
+    }
+
+    /// Detaches the first `len` nodes of the chain starting at `link`, following `next` pointers only.
+    /// Returns the head of the detached run and leaves the rest of the chain in `link`.
+    ///
+    /// # Safety
+    ///
+    /// Every node of the chain must be live and not borrowed elsewhere.
+    unsafe fn take_run(link: &mut Link<T>, len: usize) -> Link<T> {
+        let head = *link;
+        let mut last = head;
+
+        for _ in 1..len {
+            match last.and_then(|node| (*node.as_ptr()).next) {
+                None => break,
+                next => last = next,
+            }
+        }
+
+        *link = last.and_then(|node| (*node.as_ptr()).next.take());
+        head
+    }
+
+    /// Merges two chains linked by `next` pointers, taking from `a` first on ties.
+    /// Returns the head and the tail of the merged chain.
+    ///
+    /// # Safety
+    ///
+    /// Every node of both chains must be live and not borrowed elsewhere, and no node may be in both chains.
+    unsafe fn merge_chains(
+        mut a: Link<T>,
+        mut b: Link<T>,
+        compare: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> (Link<T>, Link<T>) {
+        let mut head = None;
+        let mut tail: Link<T> = None;
+
+        loop {
+            let node = match (a, b) {
+                (None, None) => break,
+                (Some(x), None) => x,
+                (None, Some(y)) => y,
+                (Some(x), Some(y)) => {
+                    if compare(&(*y.as_ptr()).value, &(*x.as_ptr()).value) == Ordering::Less {
+                        y
+                    } else {
+                        x
+                    }
+                }
+            };
+
+            if Some(node) == a {
+                a = (*node.as_ptr()).next;
+            } else {
+                b = (*node.as_ptr()).next;
+            }
+
+            match tail {
+                None => head = Some(node),
+                Some(tail) => (*tail.as_ptr()).next = Some(node),
+            }
+            tail = Some(node);
         }
+
+        (head, tail)
+    }
+
+    /// Restores the `prev` pointers and the tail from the `next` pointers, starting at the head.
+    fn relink(&mut self) {
+        let mut prev = None;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            // SAFETY: every node reached from the head by `next` pointers is live and owned by the list.
+            unsafe {
+                (*node.as_ptr()).prev = prev;
+                current = (*node.as_ptr()).next;
+            }
+            prev = Some(node);
+        }
+
+        self.tail = prev;
+    }
+
+    /// Detaches the node from the list and takes back ownership of it.
+    ///
+    /// # Safety
+    ///
+    /// `node` must belong to this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> Box<Node<T>> {
+        let node = Box::from_raw(node.as_ptr());
+
+        match node.prev {
+            None => self.head = node.next,
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+        }
+        match node.next {
+            None => self.tail = node.prev,
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+        }
+
+        self.len -= 1;
+        node
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Frees the nodes one by one, starting from the front.
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
     }
 }
 
 struct Node<T> {
     value: T,
-    next: Option<Box<Node<T>>>
+    prev: Link<T>,
+    next: Link<T>,
 }
 
 impl<T> Deref for Node<T> {
@@ -51,102 +385,379 @@ impl<T> Deref for Node<T> {
     }
 }
 
-struct NodeIterator<'a, T> {
-    current: Option<&'a Node<T>>
-}
+impl<T> Node<T> {
+    pub fn new(value: T) -> Self {
+        Node { value, prev: None, next: None }
+    }
 
-impl<'a, T> NodeIterator<'a, T> {
-    pub fn new(node: &'a Node<T>) -> Self {
-        Self { current: Some(node) }
+    /// Allocates an unlinked node. Ownership is transferred to the caller,
+    /// who must eventually release it through `Box::from_raw`.
+    fn alloc(value: T) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Node::new(value))))
     }
 }
 
-impl<'a, T> Iterator for NodeIterator<'a, T> {
-    type Item = &'a T;
+/// A cursor over a [`LinkedList`] that can move back and forth and edit the list in place.
+///
+/// The cursor points either at an element or at a "ghost" position located between
+/// the back and the front of the list: moving past either end lands on the ghost,
+/// and moving again wraps around to the other end.
+pub struct CursorMut<'a, T> {
+    current: Link<T>,
+    index: Option<usize>,
+    list: &'a mut LinkedList<T>,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(result) = self.current {
-            self.current = result.next_as_ref();
-            return Some(result);
-        }
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the position of the current element, or `None` on the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
 
-        return None;
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the cursor borrows the list mutably, so the node is live and the reference unique.
+        self.current.map(|node| unsafe { &mut (*node.as_ptr()).value })
     }
-}
 
-impl<T> Node<T> {
-    pub fn new(value: T) -> Self {
-        Node { value, next: None }
+    /// Returns the element after the current one, or the front element on the ghost position.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.head,
+            // SAFETY: the current node is live as long as the cursor borrows the list.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        // SAFETY: the next node belongs to the list too, and the cursor borrows it mutably.
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
     }
 
-    pub fn has_next(&self) -> bool {
-        self.next.is_some()
+    /// Returns the element before the current one, or the back element on the ghost position.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.tail,
+            // SAFETY: the current node is live as long as the cursor borrows the list.
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        // SAFETY: the previous node belongs to the list too, and the cursor borrows it mutably.
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).value })
     }
 
-    pub fn push(&mut self, value: T) {
-        match &mut self.next {
+    pub fn move_next(&mut self) {
+        match self.current {
             None => {
-                self.next = Some(Box::new(Node::new(value)));
-            },
+                self.current = self.list.head;
+                self.index = self.current.map(|_| 0);
+            }
             Some(node) => {
-                node.push(value)
+                // SAFETY: the current node is live as long as the cursor borrows the list.
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index = self.current.and_then(|_| self.index.map(|i| i + 1));
             }
         }
     }
 
-    fn take_next(&mut self) -> Option<T> {
-        let res = self.next.take();
-        match res {
-            None => None,
-            Some(val) => Some(val.value)
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.current.map(|_| self.list.len - 1);
+            }
+            Some(node) => {
+                // SAFETY: the current node is live as long as the cursor borrows the list.
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = self.current.and_then(|_| self.index.map(|i| i - 1));
+            }
         }
     }
 
-    fn pop(&mut self) -> Option<T> {
-        if let Some(next) = &mut self.next {
-            if let None = next.next {
-                // self.next = None;
-                return self.take_next()
-            } else {
-                return next.pop();
+    /// Inserts a value after the current element, or at the front on the ghost position.
+    pub fn insert_after(&mut self, value: T) {
+        match self.current {
+            None => self.list.push_front(value),
+            // SAFETY: the current node and its neighbours belong to the list, and `new` was just allocated.
+            Some(node) => unsafe {
+                let new = Node::alloc(value);
+                let next = (*node.as_ptr()).next;
+
+                (*new.as_ptr()).prev = Some(node);
+                (*new.as_ptr()).next = next;
+                (*node.as_ptr()).next = Some(new);
+                match next {
+                    None => self.list.tail = Some(new),
+                    Some(next) => (*next.as_ptr()).prev = Some(new),
+                }
+
+                self.list.len += 1;
+            },
+        }
+    }
+
+    /// Inserts a value before the current element, or at the back on the ghost position.
+    pub fn insert_before(&mut self, value: T) {
+        match self.current {
+            None => self.list.push_back(value),
+            // SAFETY: the current node and its neighbours belong to the list, and `new` was just allocated.
+            Some(node) => unsafe {
+                let new = Node::alloc(value);
+                let prev = (*node.as_ptr()).prev;
+
+                (*new.as_ptr()).next = Some(node);
+                (*new.as_ptr()).prev = prev;
+                (*node.as_ptr()).prev = Some(new);
+                match prev {
+                    None => self.list.head = Some(new),
+                    Some(prev) => (*prev.as_ptr()).next = Some(new),
+                }
+
+                self.list.len += 1;
+                self.index = self.index.map(|i| i + 1);
+            },
+        }
+    }
+
+    /// Removes the current element and moves the cursor to the next one.
+    /// Returns `None` on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+
+        // SAFETY: the current node belongs to the list, and the cursor moves off it before it is unlinked.
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            if self.current.is_none() {
+                self.index = None;
+            }
+            Some(self.list.unlink(node).value)
+        }
+    }
+
+    /// Detaches the elements after the current one into a new list, in O(1).
+    /// On the ghost position, the whole list is detached.
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let node = match self.current {
+            None => return std::mem::take(self.list),
+            Some(node) => node,
+        };
+
+        let index = self.index.unwrap();
+
+        // SAFETY: the current node and the ones after it belong to the list, and they end up owned
+        // by exactly one of the two lists, whose lengths account for the `index + 1` nodes kept.
+        unsafe {
+            match (*node.as_ptr()).next.take() {
+                None => LinkedList::new(),
+                Some(next) => {
+                    (*next.as_ptr()).prev = None;
+
+                    let split = LinkedList {
+                        head: Some(next),
+                        tail: self.list.tail,
+                        len: self.list.len - index - 1,
+                        marker: PhantomData,
+                    };
+
+                    self.list.tail = Some(node);
+                    self.list.len = index + 1;
+
+                    split
+                }
+            }
+        }
+    }
+
+    /// Moves all the elements of `other` after the current element, in O(1).
+    /// On the ghost position, they are inserted at the front of the list.
+    pub fn splice_after(&mut self, mut other: LinkedList<T>) {
+        let (first, last) = match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+        let len = std::mem::take(&mut other.len);
+
+        // SAFETY: `other` no longer owns its nodes, which are all handed over to this list,
+        // and the current node and its successor belong to this list.
+        unsafe {
+            let next = match self.current {
+                None => self.list.head.replace(first),
+                Some(node) => {
+                    (*first.as_ptr()).prev = Some(node);
+                    (*node.as_ptr()).next.replace(first)
+                }
+            };
+
+            (*last.as_ptr()).next = next;
+            match next {
+                None => self.list.tail = Some(last),
+                Some(next) => (*next.as_ptr()).prev = Some(last),
             }
         }
 
-        panic!();
+        self.list.len += len;
     }
+}
+
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
 
-    pub fn next_as_ref(&self) -> Option<&Node<T>> {
-        match &self.next {
-            None => return None,
-            Some(v) => return Some(&v),
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
         }
+
+        // SAFETY: the iterator borrows the list, and `len` stops it before it reaches a node already returned.
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &node.value
+        })
     }
 
-    pub fn set(&mut self, value: T) {
-        self.value = value;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
     }
+}
 
-    pub fn clear(&mut self) {
-        self.next = None;
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // SAFETY: the iterator borrows the list, and `len` stops it before it reaches a node already returned.
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &node.value
+        })
     }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
 
-    fn length_internal(&self, counter: &mut usize) {
-        *counter += 1;
-        if let Some(next) = &self.next {
-            next.length_internal(counter);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
         }
+
+        // SAFETY: the iterator borrows the list mutably, and `len` stops it before it returns a node twice.
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &mut node.value
+        })
     }
 
-    pub fn len(&self) -> usize {
-        let mut counter = 0;
-        self.length_internal(&mut counter);
-        return counter;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // SAFETY: the iterator borrows the list mutably, and `len` stops it before it returns a node twice.
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &mut node.value
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+pub struct IntoIter<T> {
+    list: LinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Node, NodeIterator, LinkedList};
+    use super::{Node, LinkedList};
 
     #[test]
     fn pop() {
@@ -173,26 +784,428 @@ mod test {
 
     #[test]
     fn len() {
-        let mut node = Node::new(5);
-        assert_eq!(1, node.len());
+        let mut list = LinkedList::new();
+        assert_eq!(0, list.len());
+        assert!(list.is_empty());
 
-        node.push(2);
-        assert_eq!(2, node.len());
+        list.push(5);
+        assert_eq!(1, list.len());
+
+        list.push_front(2);
+        assert_eq!(2, list.len());
 
-        node.push(3);
-        assert_eq!(3, node.len());
+        list.push(3);
+        assert_eq!(3, list.len());
+
+        list.pop_front();
+        list.pop_back();
+        assert_eq!(1, list.len());
+        assert!(!list.is_empty());
     }
 
     #[test]
     fn iter() {
-        let mut node = Node::new(5);
-        node.push(2);
-        node.push(3);
+        let mut list = LinkedList::new();
+        list.push(5);
+        list.push(2);
+        list.push(3);
 
-        let mut iter = NodeIterator::new(&node);
+        let mut iter = list.iter();
 
         assert_eq!(Some(&5), iter.next());
         assert_eq!(Some(&2), iter.next());
         assert_eq!(Some(&3), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn iter_double_ended() {
+        let list: LinkedList<i32> = (1..=5).collect();
+
+        let mut iter = list.iter();
+        assert_eq!(5, iter.len());
+        assert_eq!(Some(&1), iter.next());
+        assert_eq!(Some(&5), iter.next_back());
+        assert_eq!(Some(&4), iter.next_back());
+        assert_eq!(2, iter.len());
+        assert_eq!(Some(&2), iter.next());
+        assert_eq!(Some(&3), iter.next_back());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next_back());
+
+        let reversed: Vec<&i32> = list.iter().rev().collect();
+        assert_eq!(vec![&5, &4, &3, &2, &1], reversed);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list: LinkedList<i32> = (1..=4).collect();
+
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        if let Some(last) = list.iter_mut().next_back() {
+            *last += 1;
+        }
+        for value in &mut list {
+            *value += 1;
+        }
+
+        let values: Vec<&i32> = (&list).into_iter().collect();
+        assert_eq!(vec![&11, &21, &31, &42], values);
+    }
+
+    #[test]
+    fn into_iter() {
+        let list: LinkedList<String> = vec!["a", "b", "c"].into_iter().map(String::from).collect();
+
+        let mut iter = list.into_iter();
+        assert_eq!(Some(String::from("c")), iter.next_back());
+        assert_eq!(2, iter.len());
+
+        // The remaining values are dropped along with the iterator.
+        assert_eq!(Some(String::from("a")), iter.next());
+    }
+
+    #[test]
+    fn extend() {
+        let mut list: LinkedList<i32> = LinkedList::new();
+        list.extend(vec![1, 2]);
+        list.extend(3..=4);
+
+        assert_eq!(4, list.len());
+        assert_eq!(vec![1, 2, 3, 4], list.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn push_pop_both_ends() {
+        let mut list = LinkedList::new();
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+
+        assert_eq!(Some(&1), list.front());
+        assert_eq!(Some(&3), list.back());
+
+        assert_eq!(Some(1), list.pop_front());
+        assert_eq!(Some(3), list.pop_back());
+        assert_eq!(Some(2), list.pop_front());
+        assert_eq!(None, list.pop_front());
+        assert_eq!(None, list.pop_back());
+        assert_eq!(None, list.front());
+        assert_eq!(None, list.back());
+
+        list.push_front(4);
+        assert_eq!(Some(&4), list.back());
+        assert_eq!(Some(4), list.pop_back());
+    }
+
+    #[test]
+    fn front_back_mut() {
+        let mut list = LinkedList::new();
+        list.push(1);
+        list.push(2);
+
+        *list.front_mut().unwrap() = 10;
+        *list.back_mut().unwrap() += 10;
+
+        assert_eq!(Some(10), list.pop_front());
+        assert_eq!(Some(12), list.pop_front());
+    }
+
+    #[test]
+    fn clear() {
+        let mut list = LinkedList::new();
+        list.push(String::from("a"));
+        list.push(String::from("b"));
+        list.clear();
+
+        assert!(list.is_empty());
+        assert_eq!(None, list.pop());
+
+        list.push(String::from("c"));
+        assert_eq!(Some(&String::from("c")), list.front());
+    }
+
+    fn values(list: &LinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn cursor_move() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+
+        assert_eq!(Some(0), cursor.index());
+        assert_eq!(Some(&mut 1), cursor.current());
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(&mut 3), cursor.current());
+        assert_eq!(Some(&mut 2), cursor.peek_prev());
+        assert_eq!(None, cursor.peek_next());
+
+        // Past the back is the ghost position, then the cursor wraps around.
+        cursor.move_next();
+        assert_eq!(None, cursor.index());
+        assert_eq!(None, cursor.current());
+        assert_eq!(Some(&mut 1), cursor.peek_next());
+        assert_eq!(Some(&mut 3), cursor.peek_prev());
+        cursor.move_next();
+        assert_eq!(Some(&mut 1), cursor.current());
+
+        cursor.move_prev();
+        assert_eq!(None, cursor.current());
+        cursor.move_prev();
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(&mut 3), cursor.current());
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        let mut cursor = empty.cursor_back_mut();
+        assert_eq!(None, cursor.current());
+        cursor.move_next();
+        assert_eq!(None, cursor.current());
+    }
+
+    #[test]
+    fn cursor_edit_current() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_back_mut();
+
+        *cursor.current().unwrap() = 30;
+        cursor.move_prev();
+        *cursor.current().unwrap() = 20;
+
+        assert_eq!(vec![1, 20, 30], values(&list));
+    }
+
+    #[test]
+    fn cursor_insert() {
+        let mut list: LinkedList<i32> = vec![2, 4].into_iter().collect();
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.insert_before(1);
+        assert_eq!(Some(1), cursor.index());
+        cursor.insert_after(3);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_after(5);
+        cursor.move_next();
+        cursor.move_next();
+
+        // On the ghost position, `insert_after` pushes at the front, `insert_before` at the back.
+        cursor.insert_after(0);
+        cursor.insert_before(6);
+        assert_eq!(None, cursor.current());
+
+        assert_eq!(vec![0, 1, 2, 3, 4, 5, 6], values(&list));
+        assert_eq!(7, list.len());
+        assert_eq!(Some(&6), list.back());
+    }
+
+    #[test]
+    fn cursor_remove() {
+        let mut list: LinkedList<i32> = (1..=4).collect();
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.move_next();
+        assert_eq!(Some(2), cursor.remove_current());
+        assert_eq!(Some(&mut 3), cursor.current());
+        assert_eq!(Some(1), cursor.index());
+
+        cursor.move_next();
+        assert_eq!(Some(4), cursor.remove_current());
+        assert_eq!(None, cursor.current());
+        assert_eq!(None, cursor.remove_current());
+
+        cursor.move_next();
+        assert_eq!(Some(1), cursor.remove_current());
+
+        assert_eq!(vec![3], values(&list));
+        assert_eq!(Some(&3), list.front());
+        assert_eq!(Some(&3), list.back());
+    }
+
+    #[test]
+    fn cursor_split_after() {
+        let mut list: LinkedList<i32> = (1..=5).collect();
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.move_next();
+        let mut tail = cursor.split_after();
+        assert_eq!(vec![1, 2], values(&list));
+        assert_eq!(vec![3, 4, 5], values(&tail));
+        assert_eq!(2, list.len());
+        assert_eq!(3, tail.len());
+        assert_eq!(Some(&2), list.back());
+
+        let mut cursor = tail.cursor_back_mut();
+        assert!(cursor.split_after().is_empty());
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert!(tail.is_empty());
+        assert_eq!(vec![3, 4, 5], values(&all));
+    }
+
+    #[test]
+    fn cursor_splice_after() {
+        let mut list: LinkedList<i32> = vec![1, 5].into_iter().collect();
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.splice_after((2..=4).collect());
+        assert_eq!(Some(&mut 1), cursor.current());
+        cursor.splice_after(LinkedList::new());
+
+        cursor.move_prev();
+        cursor.splice_after(vec![-1, 0].into_iter().collect());
+
+        cursor.move_prev();
+        assert_eq!(Some(6), cursor.index());
+        assert_eq!(Some(&mut 5), cursor.current());
+        cursor.splice_after(vec![6, 7].into_iter().collect());
+
+        assert_eq!(vec![-1, 0, 1, 2, 3, 4, 5, 6, 7], values(&list));
+        assert_eq!(9, list.len());
+        assert_eq!(Some(&7), list.back());
+        assert_eq!(vec![7, 6, 5, 4, 3, 2, 1, 0, -1], list.iter().rev().copied().collect::<Vec<_>>());
+
+        let mut empty = LinkedList::new();
+        empty.cursor_front_mut().splice_after(vec![1].into_iter().collect());
+        assert_eq!(vec![1], values(&empty));
+        assert_eq!(Some(&1), empty.back());
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list: LinkedList<i32> = (1..=4).collect();
+        list.reverse();
+
+        assert_eq!(vec![4, 3, 2, 1], values(&list));
+        assert_eq!(vec![1, 2, 3, 4], list.iter().rev().copied().collect::<Vec<_>>());
+        assert_eq!(Some(&4), list.front());
+        assert_eq!(Some(&1), list.back());
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn sort() {
+        let mut list: LinkedList<i32> = vec![5, 1, 4, 2, 3, 2, 0].into_iter().collect();
+        list.sort();
+
+        assert_eq!(vec![0, 1, 2, 2, 3, 4, 5], values(&list));
+        assert_eq!(vec![5, 4, 3, 2, 2, 1, 0], list.iter().rev().copied().collect::<Vec<_>>());
+        assert_eq!(7, list.len());
+        assert_eq!(Some(&5), list.back());
+
+        list.push(-1);
+        assert_eq!(Some(-1), list.pop_back());
+    }
+
+    #[test]
+    fn sort_is_stable() {
+        let mut list: LinkedList<(i32, usize)> = vec![3, 1, 3, 2, 1, 3, 2].into_iter().enumerate().map(|(i, k)| (k, i)).collect();
+        list.sort_by_key(|e| e.0);
+
+        let sorted: Vec<(i32, usize)> = list.into_iter().collect();
+        assert_eq!(vec![(1, 1), (1, 4), (2, 3), (2, 6), (3, 0), (3, 2), (3, 5)], sorted);
+    }
+
+    #[test]
+    fn sort_by() {
+        let mut list: LinkedList<i32> = (0..10).collect();
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!((0..10).rev().collect::<Vec<_>>(), values(&list));
+
+        let mut single: LinkedList<i32> = (0..1).collect();
+        single.sort();
+        assert_eq!(vec![0], values(&single));
+    }
+
+    #[test]
+    fn merge() {
+        let mut a: LinkedList<(i32, char)> = vec![(1, 'a'), (3, 'a'), (5, 'a')].into_iter().collect();
+        let b: LinkedList<(i32, char)> = vec![(0, 'b'), (3, 'b'), (6, 'b')].into_iter().collect();
+
+        a.merge(b);
+
+        // Compared as tuples: (3, 'a') < (3, 'b').
+        let merged: Vec<(i32, char)> = a.iter().copied().collect();
+        assert_eq!(vec![(0, 'b'), (1, 'a'), (3, 'a'), (3, 'b'), (5, 'a'), (6, 'b')], merged);
+        assert_eq!(6, a.len());
+        assert_eq!(Some(&(6, 'b')), a.back());
+
+        let mut empty = LinkedList::new();
+        empty.merge((1..=2).collect());
+        assert_eq!(vec![1, 2], values(&empty));
+        empty.merge(LinkedList::new());
+        assert_eq!(vec![1, 2], values(&empty));
+    }
+
+    #[test]
+    fn dedup() {
+        let mut list: LinkedList<i32> = vec![1, 1, 2, 3, 3, 3, 1, 4, 4].into_iter().collect();
+        list.dedup();
+
+        assert_eq!(vec![1, 2, 3, 1, 4], values(&list));
+        assert_eq!(5, list.len());
+        assert_eq!(Some(&4), list.back());
+        assert_eq!(vec![4, 1, 3, 2, 1], list.iter().rev().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dedup_by() {
+        let mut list: LinkedList<i32> = vec![1, 2, 4, 5, 7, 10].into_iter().collect();
+        list.dedup_by(|a, b| a - b < 2);
+
+        assert_eq!(vec![1, 4, 7, 10], values(&list));
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    fn long_list() -> LinkedList<usize> {
+        let mut list = LinkedList::new();
+        for value in 0..STRESS {
+            list.push(value);
+        }
+        list
+    }
+
+    #[test]
+    fn stress_len() {
+        let list = long_list();
+        assert_eq!(STRESS, list.len());
+        assert_eq!(STRESS, list.iter().count());
+        assert_eq!(STRESS, list.iter().rev().count());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn stress_push_pop() {
+        let mut list = long_list();
+
+        list.push(STRESS);
+        assert_eq!(Some(STRESS), list.pop());
+        assert_eq!(Some(STRESS - 1), list.pop());
+        assert_eq!(STRESS - 1, list.len());
+
+        while list.pop_front().is_some() {}
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn stress_drop() {
+        drop(long_list());
+    }
+
+    #[test]
+    fn stress_sort() {
+        let mut list: LinkedList<usize> = (0..STRESS).map(|i| (i * 7919) % STRESS).collect();
+        list.sort();
+
+        assert_eq!(STRESS, list.len());
+        assert!(list.iter().copied().eq(0..STRESS));
+        assert!(list.iter().rev().copied().eq((0..STRESS).rev()));
+    }
+}
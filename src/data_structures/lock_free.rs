@@ -0,0 +1,365 @@
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned, Shared};
+
+/// A lock-free LIFO stack (Treiber stack) that can be shared between threads.
+///
+/// Popped nodes are reclaimed through epoch-based garbage collection, so that
+/// a node is only freed once no other thread can still be reading it.
+pub struct TreiberStack<T> {
+    head: Atomic<StackNode<T>>,
+}
+
+struct StackNode<T> {
+    value: ManuallyDrop<T>,
+    next: Atomic<StackNode<T>>,
+}
+
+// SAFETY: the stack owns its values, which are moved in and out of it and never shared between threads,
+// so `T: Send` is enough for the stack to be both sent and shared.
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        Self { head: Atomic::null() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Acquire, &guard).is_null()
+    }
+
+    pub fn push(&self, value: T) {
+        let mut node = Owned::new(StackNode { value: ManuallyDrop::new(value), next: Atomic::null() });
+        let guard = epoch::pin();
+
+        loop {
+            let head = self.head.load(Relaxed, &guard);
+            node.next.store(head, Relaxed);
+
+            match self.head.compare_exchange(head, node, Release, Relaxed, &guard) {
+                Ok(_) => break,
+                Err(e) => node = e.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+
+        loop {
+            let head = self.head.load(Acquire, &guard);
+            // SAFETY: the guard pins the epoch, so a node popped by another thread since the load is only
+            // freed once this thread is unpinned. The acquire load pairs with the release CAS of `push`,
+            // which publishes the value and `next` of the node.
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Relaxed, &guard);
+
+            if self.head.compare_exchange(head, next, Relaxed, Relaxed, &guard).is_ok() {
+                // SAFETY: only the thread whose CAS unlinked the node reads its value, and the node is never
+                // linked again, so the value is moved out once. It is in a `ManuallyDrop`, so destroying the
+                // node does not drop it again, and the destruction waits for every thread pinned meanwhile.
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(ptr::read(&node.value)));
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// A lock-free FIFO queue (Michael–Scott queue) that can be shared between threads.
+///
+/// The queue always starts with a sentinel node: `head` points at the sentinel and
+/// the first value is stored in the node after it. Dequeued nodes are reclaimed
+/// through epoch-based garbage collection.
+pub struct MsQueue<T> {
+    head: Atomic<QueueNode<T>>,
+    tail: Atomic<QueueNode<T>>,
+}
+
+struct QueueNode<T> {
+    /// Uninitialized in the sentinel, whose value has already been dequeued (or never existed).
+    value: MaybeUninit<T>,
+    next: Atomic<QueueNode<T>>,
+}
+
+// SAFETY: as for `TreiberStack`, values are moved in and out of the queue and never shared between threads.
+unsafe impl<T: Send> Send for MsQueue<T> {}
+unsafe impl<T: Send> Sync for MsQueue<T> {}
+
+impl<T> MsQueue<T> {
+    pub fn new() -> Self {
+        let queue = Self { head: Atomic::null(), tail: Atomic::null() };
+        let sentinel = Owned::new(QueueNode { value: MaybeUninit::uninit(), next: Atomic::null() });
+
+        // SAFETY: the queue is not shared with any thread yet, so no guard is needed to protect the sentinel.
+        unsafe {
+            let sentinel = sentinel.into_shared(epoch::unprotected());
+            queue.head.store(sentinel, Relaxed);
+            queue.tail.store(sentinel, Relaxed);
+        }
+
+        queue
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        let head = self.head.load(Acquire, &guard);
+        // SAFETY: `head` always points at the sentinel, which is never null, and the guard pins the epoch,
+        // so the sentinel is not freed while it is read, even if another thread dequeues it meanwhile.
+        unsafe { head.deref() }.next.load(Acquire, &guard).is_null()
+    }
+
+    pub fn push(&self, value: T) {
+        let guard = epoch::pin();
+        let node = Owned::new(QueueNode { value: MaybeUninit::new(value), next: Atomic::null() }).into_shared(&guard);
+
+        loop {
+            let tail = self.tail.load(Acquire, &guard);
+            // SAFETY: `tail` is never null, and `pop_with` moves it off a node before destroying the node,
+            // which waits for the guard to unpin anyway.
+            let tail_node = unsafe { tail.deref() };
+            let next = tail_node.next.load(Acquire, &guard);
+
+            if !next.is_null() {
+                // Another push linked its node but did not move the tail yet: help it.
+                let _ = self.tail.compare_exchange(tail, next, Release, Relaxed, &guard);
+                continue;
+            }
+
+            if tail_node.next.compare_exchange(Shared::null(), node, Release, Relaxed, &guard).is_ok() {
+                let _ = self.tail.compare_exchange(tail, node, Release, Relaxed, &guard);
+                return;
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        self.pop_with(&guard)
+    }
+
+    fn pop_with(&self, guard: &Guard) -> Option<T> {
+        loop {
+            let head = self.head.load(Acquire, guard);
+            // SAFETY: `head` is never null and the guard pins the epoch, as in `is_empty`.
+            let next = unsafe { head.deref() }.next.load(Acquire, guard);
+            // SAFETY: the acquire load of `next` pairs with the release CAS of `push` that linked the node,
+            // so its value is initialized and visible. The node is behind the sentinel, protected likewise.
+            let next_node = unsafe { next.as_ref() }?;
+
+            if self.head.compare_exchange(head, next, Release, Relaxed, guard).is_ok() {
+                // The tail must never point to a reclaimed node.
+                let tail = self.tail.load(Relaxed, guard);
+                if head == tail {
+                    let _ = self.tail.compare_exchange(tail, next, Release, Relaxed, guard);
+                }
+
+                // SAFETY: only the thread whose CAS moved `head` to `next` reads its value, and `next` becomes
+                // the new sentinel whose value is never read again. The old sentinel is unlinked, and it is only
+                // freed once every thread pinned meanwhile, which may still read it, is unpinned.
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(next_node.value.assume_init_read());
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for MsQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MsQueue<T> {
+    fn drop(&mut self) {
+        // SAFETY: `&mut self` means no other thread can access the queue anymore, so nodes can be freed
+        // at once without a pinned guard. The sentinel is never null, and its value is already moved out
+        // or was never set, which `MaybeUninit` does not drop.
+        unsafe {
+            let guard = epoch::unprotected();
+
+            while self.pop_with(guard).is_some() {}
+
+            let sentinel = self.head.load(Relaxed, guard);
+            drop(sentinel.into_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use super::{MsQueue, TreiberStack};
+
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 20_000;
+
+    #[test]
+    fn stack_lifo() {
+        let stack = TreiberStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(None, stack.pop());
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        assert!(!stack.is_empty());
+        assert_eq!(Some(3), stack.pop());
+        assert_eq!(Some(2), stack.pop());
+        stack.push(4);
+        assert_eq!(Some(4), stack.pop());
+        assert_eq!(Some(1), stack.pop());
+        assert_eq!(None, stack.pop());
+    }
+
+    #[test]
+    fn queue_fifo() {
+        let queue = MsQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(None, queue.pop());
+
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+
+        assert!(!queue.is_empty());
+        assert_eq!(Some(1), queue.pop());
+        assert_eq!(Some(2), queue.pop());
+        queue.push(4);
+        assert_eq!(Some(3), queue.pop());
+        assert_eq!(Some(4), queue.pop());
+        assert_eq!(None, queue.pop());
+        assert!(queue.is_empty());
+    }
+
+    struct Counted<'a>(&'a AtomicUsize);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn drop_remaining_values() {
+        let drops = AtomicUsize::new(0);
+
+        let stack = TreiberStack::new();
+        let queue = MsQueue::new();
+        for _ in 0..10 {
+            stack.push(Counted(&drops));
+            queue.push(Counted(&drops));
+        }
+
+        drop(stack.pop());
+        drop(queue.pop());
+        assert_eq!(2, drops.load(Ordering::Relaxed));
+
+        drop(stack);
+        drop(queue);
+        assert_eq!(20, drops.load(Ordering::Relaxed));
+    }
+
+    /// Every thread pushes its own values and pops as many values as it pushed,
+    /// then all the popped values must be exactly the pushed ones.
+    #[test]
+    fn stack_stress() {
+        let stack = TreiberStack::new();
+
+        let mut popped: Vec<usize> = thread::scope(|s| {
+            let handles: Vec<_> = (0..THREADS).map(|t| {
+                let stack = &stack;
+                s.spawn(move || {
+                    let mut popped = Vec::with_capacity(PER_THREAD);
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 1 {
+                            popped.extend(stack.pop());
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            }).collect();
+
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+
+        while let Some(value) = stack.pop() {
+            popped.push(value);
+        }
+
+        popped.sort_unstable();
+        assert_eq!(popped, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    /// Producers push increasing values while consumers pop them concurrently: every value
+    /// is received exactly once, and values from a given producer are received in order.
+    #[test]
+    fn queue_stress() {
+        let queue = MsQueue::new();
+        let remaining = AtomicUsize::new(THREADS * PER_THREAD);
+
+        let received: Vec<Vec<(usize, usize)>> = thread::scope(|s| {
+            for t in 0..THREADS {
+                let queue = &queue;
+                s.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        queue.push((t, i));
+                    }
+                });
+            }
+
+            let consumers: Vec<_> = (0..THREADS).map(|_| {
+                let (queue, remaining) = (&queue, &remaining);
+                s.spawn(move || {
+                    let mut received = Vec::new();
+                    while remaining.load(Ordering::Relaxed) > 0 {
+                        if let Some(value) = queue.pop() {
+                            remaining.fetch_sub(1, Ordering::Relaxed);
+                            received.push(value);
+                        }
+                    }
+                    received
+                })
+            }).collect();
+
+            consumers.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for values in &received {
+            for t in 0..THREADS {
+                let from_producer: Vec<usize> = values.iter().filter(|v| v.0 == t).map(|v| v.1).collect();
+                assert!(from_producer.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+
+        let mut all: Vec<(usize, usize)> = received.into_iter().flatten().collect();
+        all.sort_unstable();
+        assert_eq!(all.len(), THREADS * PER_THREAD);
+        assert!(all.iter().enumerate().all(|(n, v)| *v == (n / PER_THREAD, n % PER_THREAD)));
+        assert!(queue.is_empty());
+    }
+}
@@ -1,3 +1,15 @@
 pub mod tree;
 pub mod binary_tree;
-pub mod linked_list;
\ No newline at end of file
+pub mod linked_list;
+pub mod persistent_tree;
+pub mod interval_tree;
+pub mod cons_list;
+pub mod trie;
+pub mod graph;
+pub mod heap;
+pub mod probabilistic;
+pub mod ring_buffer;
+pub mod rope;
+#[cfg(feature = "concurrent")]
+pub mod lock_free;
+mod render;
\ No newline at end of file
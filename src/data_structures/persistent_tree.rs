@@ -0,0 +1,369 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+/// An immutable ordered collection.
+///
+/// `insert` and `remove` leave the tree untouched and return a new version that
+/// shares all the nodes outside of the modified path with the original one,
+/// so that old versions stay valid and each update only allocates O(log n) nodes.
+/// Cloning a tree is O(1).
+pub struct PersistentTree<T> where T: PartialOrd {
+    root: Link<T>,
+    len: usize,
+}
+
+impl<T: PartialOrd> PersistentTree<T> {
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let mut current = self.root.as_ref();
+
+        while let Some(node) = current {
+            if *value < *node.value {
+                current = node.left.as_ref();
+            } else if *node.value < *value {
+                current = node.right.as_ref();
+            } else {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns a new tree that contains `value` in addition to the values of this tree.
+    pub fn insert(&self, value: T) -> Self {
+        let root = Node::insert(&self.root, Rc::new(value));
+
+        Self { root: Some(root), len: self.len + 1 }
+    }
+
+    /// Returns a new tree without one occurrence of `value`.
+    /// If the value is absent, the returned tree shares all its nodes with this one.
+    pub fn remove(&self, value: &T) -> Self {
+        match Node::remove(&self.root, value) {
+            None => self.clone(),
+            Some(root) => Self { root, len: self.len - 1 },
+        }
+    }
+
+    /// Returns an iterator over the values of the tree in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+}
+
+impl<T: PartialOrd> Clone for PersistentTree<T> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), len: self.len }
+    }
+}
+
+impl<T: PartialOrd> Default for PersistentTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> PartialEq for PersistentTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialOrd + Debug> Debug for PersistentTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for PersistentTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for value in iter {
+            tree = tree.insert(value);
+        }
+        tree
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+impl<'a, T: PartialOrd> IntoIterator for &'a PersistentTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An AVL node. Values are reference counted so that copying a node along
+/// the modified path never requires cloning the value itself.
+struct Node<T> {
+    value: Rc<T>,
+    left: Link<T>,
+    right: Link<T>,
+    height: usize,
+}
+
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+impl<T: PartialOrd> Node<T> {
+    fn make(value: Rc<T>, left: Link<T>, right: Link<T>) -> Rc<Self> {
+        let height = 1 + height(&left).max(height(&right));
+        Rc::new(Self { value, left, right, height })
+    }
+
+    /// Creates a node from its parts, rotating if the heights of the subtrees differ by more than one.
+    fn balance(value: Rc<T>, left: Link<T>, right: Link<T>) -> Rc<Self> {
+        let (hl, hr) = (height(&left), height(&right));
+
+        if hl > hr + 1 {
+            let l = left.as_ref().unwrap();
+            if height(&l.left) >= height(&l.right) {
+                let new_right = Node::make(value, l.right.clone(), right);
+                Node::make(l.value.clone(), l.left.clone(), Some(new_right))
+            } else {
+                let lr = l.right.as_ref().unwrap();
+                let new_left = Node::make(l.value.clone(), l.left.clone(), lr.left.clone());
+                let new_right = Node::make(value, lr.right.clone(), right);
+                Node::make(lr.value.clone(), Some(new_left), Some(new_right))
+            }
+        } else if hr > hl + 1 {
+            let r = right.as_ref().unwrap();
+            if height(&r.right) >= height(&r.left) {
+                let new_left = Node::make(value, left, r.left.clone());
+                Node::make(r.value.clone(), Some(new_left), r.right.clone())
+            } else {
+                let rl = r.left.as_ref().unwrap();
+                let new_left = Node::make(value, left, rl.left.clone());
+                let new_right = Node::make(r.value.clone(), rl.right.clone(), r.right.clone());
+                Node::make(rl.value.clone(), Some(new_left), Some(new_right))
+            }
+        } else {
+            Node::make(value, left, right)
+        }
+    }
+
+    fn insert(link: &Link<T>, value: Rc<T>) -> Rc<Self> {
+        match link {
+            None => Node::make(value, None, None),
+            Some(node) => {
+                if *value < *node.value {
+                    let left = Node::insert(&node.left, value);
+                    Node::balance(node.value.clone(), Some(left), node.right.clone())
+                } else {
+                    let right = Node::insert(&node.right, value);
+                    Node::balance(node.value.clone(), node.left.clone(), Some(right))
+                }
+            }
+        }
+    }
+
+    /// Returns the new subtree, or `None` if the value was not found.
+    fn remove(link: &Link<T>, value: &T) -> Option<Link<T>> {
+        let node = link.as_ref()?;
+
+        if *value < *node.value {
+            let left = Node::remove(&node.left, value)?;
+            Some(Some(Node::balance(node.value.clone(), left, node.right.clone())))
+        } else if *node.value < *value {
+            let right = Node::remove(&node.right, value)?;
+            Some(Some(Node::balance(node.value.clone(), node.left.clone(), right)))
+        } else {
+            match (&node.left, &node.right) {
+                (None, right) => Some(right.clone()),
+                (left, None) => Some(left.clone()),
+                (left, Some(right)) => {
+                    let (min, right) = Node::remove_min(right);
+                    Some(Some(Node::balance(min, left.clone(), right)))
+                }
+            }
+        }
+    }
+
+    /// Returns the smallest value of the subtree and the subtree without it.
+    fn remove_min(node: &Rc<Self>) -> (Rc<T>, Link<T>) {
+        match &node.left {
+            None => (node.value.clone(), node.right.clone()),
+            Some(left) => {
+                let (min, left) = Node::remove_min(left);
+                (min, Some(Node::balance(node.value.clone(), left, node.right.clone())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::redux::store::Store;
+
+    use super::{height, PersistentTree};
+
+    fn values(tree: &PersistentTree<i32>) -> Vec<i32> {
+        tree.iter().copied().collect()
+    }
+
+    #[test]
+    fn empty() {
+        let tree: PersistentTree<i32> = PersistentTree::default();
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert!(!tree.contains(&1));
+        assert_eq!(tree.iter().next(), None);
+    }
+
+    #[test]
+    fn insert_keeps_previous_version() {
+        let v0 = PersistentTree::new();
+        let v1 = v0.insert(5);
+        let v2 = v1.insert(3);
+        let v3 = v2.insert(8);
+
+        assert!(v0.is_empty());
+        assert_eq!(values(&v1), vec![5]);
+        assert_eq!(values(&v2), vec![3, 5]);
+        assert_eq!(values(&v3), vec![3, 5, 8]);
+        assert_eq!(v3.len(), 3);
+        assert!(v3.contains(&8));
+        assert!(!v2.contains(&8));
+    }
+
+    #[test]
+    fn remove_keeps_previous_version() {
+        let tree: PersistentTree<i32> = (0..10).collect();
+        let removed = tree.remove(&4).remove(&0).remove(&9);
+
+        assert_eq!(values(&tree), (0..10).collect::<Vec<_>>());
+        assert_eq!(values(&removed), vec![1, 2, 3, 5, 6, 7, 8]);
+        assert_eq!(removed.len(), 7);
+    }
+
+    #[test]
+    fn remove_absent() {
+        let tree: PersistentTree<i32> = (0..10).collect();
+        let same = tree.remove(&42);
+
+        assert_eq!(same.len(), 10);
+        assert!(Rc::ptr_eq(tree.root.as_ref().unwrap(), same.root.as_ref().unwrap()));
+    }
+
+    #[test]
+    fn duplicates() {
+        let tree: PersistentTree<i32> = vec![2, 1, 2, 2].into_iter().collect();
+
+        assert_eq!(values(&tree), vec![1, 2, 2, 2]);
+        assert_eq!(values(&tree.remove(&2)), vec![1, 2, 2]);
+    }
+
+    #[test]
+    fn structural_sharing() {
+        let tree: PersistentTree<i32> = (0..1000).collect();
+        let updated = tree.insert(1000);
+
+        let old_root = tree.root.as_ref().unwrap();
+        let new_root = updated.root.as_ref().unwrap();
+
+        // The new value goes to the far right: the left subtree is shared as is.
+        assert!(!Rc::ptr_eq(old_root, new_root));
+        assert!(Rc::ptr_eq(old_root.left.as_ref().unwrap(), new_root.left.as_ref().unwrap()));
+        assert!(Rc::ptr_eq(&old_root.value, &new_root.value));
+    }
+
+    #[test]
+    fn balanced() {
+        let tree: PersistentTree<i32> = (0..1024).collect();
+        assert_eq!(height(&tree.root), 11);
+
+        let tree = (0..512).fold(tree, |t, v| t.remove(&(v * 2)));
+        assert_eq!(tree.len(), 512);
+        assert!(height(&tree.root) <= 11);
+        assert_eq!(values(&tree), (0..512).map(|v| v * 2 + 1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn equality() {
+        let a: PersistentTree<i32> = vec![1, 2, 3].into_iter().collect();
+        let b: PersistentTree<i32> = vec![3, 1, 2].into_iter().collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, b.insert(4));
+        assert_eq!(format!("{:?}", a), "{1, 2, 3}");
+    }
+
+    #[derive(Clone, Default)]
+    struct State {
+        tags: PersistentTree<String>,
+    }
+
+    enum Action {
+        Tag(String),
+        Untag(String),
+    }
+
+    fn mutate(state: State, action: Action) -> State {
+        match action {
+            Action::Tag(tag) => State { tags: state.tags.insert(tag) },
+            Action::Untag(tag) => State { tags: state.tags.remove(&tag) },
+        }
+    }
+
+    #[test]
+    fn redux_state() {
+        let mut store = Store::new(State::default(), &mutate);
+
+        store.dispatch(Action::Tag("b".to_owned()));
+        store.dispatch(Action::Tag("a".to_owned()));
+
+        let saved = store.get_state().clone();
+
+        store.dispatch(Action::Untag("b".to_owned()));
+
+        let tags: Vec<&String> = store.get_state().tags.iter().collect();
+        assert_eq!(tags, vec!["a"]);
+
+        let saved: Vec<&String> = saved.tags.iter().collect();
+        assert_eq!(saved, vec!["a", "b"]);
+    }
+}
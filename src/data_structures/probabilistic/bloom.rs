@@ -0,0 +1,211 @@
+use std::f64::consts::LN_2;
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use super::{indices, InvalidError, MergeError};
+
+/// A set that can tell that an item was never inserted, but only that it probably was.
+///
+/// Every item sets a few bits picked by hashing it, and an item is reported as present when all its bits
+/// are set, which may happen by chance after enough insertions: a false positive. There are no false
+/// negatives, and the rate of false positives is chosen when creating the filter for an expected number of items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawBloomFilter")]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+}
+
+/// A filter as deserialized, before its fields are checked against each other.
+#[derive(Deserialize)]
+struct RawBloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+}
+
+impl TryFrom<RawBloomFilter> for BloomFilter {
+    type Error = InvalidError;
+
+    fn try_from(raw: RawBloomFilter) -> Result<Self, InvalidError> {
+        if raw.bit_count == 0 || raw.hash_count == 0 {
+            return Err(InvalidError::Parameters);
+        }
+        if raw.bits.len() as u64 != raw.bit_count.div_ceil(64) {
+            return Err(InvalidError::Length);
+        }
+        Ok(Self { bits: raw.bits, bit_count: raw.bit_count, hash_count: raw.hash_count })
+    }
+}
+
+impl BloomFilter {
+    /// Returns a filter sized so that, after `expected_items` insertions, an item that was not inserted
+    /// is reported as present with a probability of `false_positive_rate`.
+    ///
+    /// Panics if `false_positive_rate` is not strictly between 0 and 1.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "the false positive rate must be between 0 and 1, not {}",
+            false_positive_rate,
+        );
+
+        let items = expected_items.max(1) as f64;
+        let bit_count = (-items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil();
+        let hash_count = (bit_count / items * LN_2).round().max(1.0);
+        Self::with_size(bit_count as u64, hash_count as u32)
+    }
+
+    /// Returns a filter of `bit_count` bits, setting `hash_count` bits per item.
+    ///
+    /// Panics if either is 0.
+    pub fn with_size(bit_count: u64, hash_count: u32) -> Self {
+        assert!(bit_count > 0 && hash_count > 0, "a bloom filter needs bits and hashes");
+        Self { bits: vec![0; bit_count.div_ceil(64) as usize], bit_count, hash_count }
+    }
+
+    pub fn bit_count(&self) -> u64 {
+        self.bit_count
+    }
+
+    pub fn hash_count(&self) -> u32 {
+        self.hash_count
+    }
+
+    /// Adds the item, and returns `false` if it was probably already present.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let mut added = false;
+        for index in indices(item, self.hash_count, self.bit_count) {
+            let (word, mask) = ((index / 64) as usize, 1 << (index % 64));
+            added |= self.bits[word] & mask == 0;
+            self.bits[word] |= mask;
+        }
+        added
+    }
+
+    /// Returns `false` if the item was never inserted, and `true` if it probably was.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        indices(item, self.hash_count, self.bit_count).all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    /// Returns the probability that an item that was not inserted is reported as present,
+    /// given the bits set so far.
+    pub fn false_positive_rate(&self) -> f64 {
+        let set: u64 = self.bits.iter().map(|word| u64::from(word.count_ones())).sum();
+        (set as f64 / self.bit_count as f64).powi(self.hash_count as i32)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// Adds the items of `other`, which must have the same size, as if they were inserted in this filter.
+    pub fn merge(&mut self, other: &BloomFilter) -> Result<(), MergeError> {
+        if (self.bit_count, self.hash_count, self.bits.len()) != (other.bit_count, other.hash_count, other.bits.len()) {
+            return Err(MergeError::Incompatible);
+        }
+
+        for (word, other) in self.bits.iter_mut().zip(&other.bits) {
+            *word |= other;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use flexbuffers::FlexbufferSerializer;
+    use serde::Serialize;
+
+    use crate::data_structures::probabilistic::MergeError;
+    use crate::serde::flexbuffer::Container;
+
+    use super::BloomFilter;
+
+    #[test]
+    fn sizing() {
+        let filter = BloomFilter::new(1000, 0.01);
+        // About 9.6 bits and 7 hashes per item for 1%.
+        assert_eq!(filter.bit_count(), 9586);
+        assert_eq!(filter.hash_count(), 7);
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn false_positives() {
+        const ITEMS: u32 = 10_000;
+
+        let mut filter = BloomFilter::new(ITEMS as usize, 0.01);
+        for i in 0..ITEMS {
+            filter.insert(&i);
+        }
+
+        assert!((0..ITEMS).all(|i| filter.contains(&i)));
+        let false_positives = (ITEMS..ITEMS * 11).filter(|i| filter.contains(i)).count();
+        let rate = false_positives as f64 / (10 * ITEMS) as f64;
+        assert!(rate > 0.005 && rate < 0.015, "false positive rate {}", rate);
+        assert!((filter.false_positive_rate() - 0.01).abs() < 0.002);
+    }
+
+    #[test]
+    fn insert() {
+        let mut filter = BloomFilter::new(100, 0.001);
+        assert!(filter.insert("apple"));
+        assert!(!filter.insert("apple"));
+        assert!(filter.contains("apple"));
+        assert!(!filter.contains("pear"));
+
+        filter.clear();
+        assert!(!filter.contains("apple"));
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = BloomFilter::new(100, 0.01);
+        let mut b = BloomFilter::new(100, 0.01);
+        a.insert("a");
+        b.insert("b");
+
+        a.merge(&b).unwrap();
+        assert!(a.contains("a") && a.contains("b"));
+
+        assert_eq!(a.merge(&BloomFilter::new(100, 0.1)), Err(MergeError::Incompatible));
+    }
+
+    #[test]
+    fn flexbuffer_roundtrip() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        for word in ["cache", "key", "value"] {
+            filter.insert(word);
+        }
+
+        let mut serializer = FlexbufferSerializer::new();
+        filter.serialize(&mut serializer).unwrap();
+        let read: BloomFilter = Container::new(serializer.view()).read().unwrap();
+
+        assert_eq!(read, filter);
+        assert!(read.contains("key"));
+    }
+
+    #[test]
+    fn flexbuffer_invalid() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        filter.bits.pop();
+
+        let mut serializer = FlexbufferSerializer::new();
+        filter.serialize(&mut serializer).unwrap();
+        assert!(Container::<BloomFilter>::new(serializer.view()).read().is_err());
+
+        filter.bit_count = 0;
+        filter.bits.clear();
+        let mut serializer = FlexbufferSerializer::new();
+        filter.serialize(&mut serializer).unwrap();
+        assert!(Container::<BloomFilter>::new(serializer.view()).read().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use std::f64::consts::E;
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use super::{indices, InvalidError, MergeError};
+
+/// Estimates how many times every item of a stream was seen, in a fixed amount of memory.
+///
+/// Every item is counted in one counter of each row, picked by hashing it, and its estimate is the smallest
+/// of its counters. Collisions only add to counters, so estimates are never below the actual counts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawCountMinSketch")]
+pub struct CountMinSketch {
+    width: u64,
+    depth: u32,
+    /// The rows of counters, one after the other.
+    counters: Vec<u64>,
+    total: u64,
+}
+
+/// A sketch as deserialized, before its fields are checked against each other.
+#[derive(Deserialize)]
+struct RawCountMinSketch {
+    width: u64,
+    depth: u32,
+    counters: Vec<u64>,
+    total: u64,
+}
+
+impl TryFrom<RawCountMinSketch> for CountMinSketch {
+    type Error = InvalidError;
+
+    fn try_from(raw: RawCountMinSketch) -> Result<Self, InvalidError> {
+        if raw.width == 0 || raw.depth == 0 {
+            return Err(InvalidError::Parameters);
+        }
+        if raw.width.checked_mul(u64::from(raw.depth)) != Some(raw.counters.len() as u64) {
+            return Err(InvalidError::Length);
+        }
+        Ok(Self { width: raw.width, depth: raw.depth, counters: raw.counters, total: raw.total })
+    }
+}
+
+impl CountMinSketch {
+    /// Returns a sketch whose estimates exceed the actual counts by at most `error` times the total count,
+    /// with a probability of `1 - failure_rate`.
+    ///
+    /// Panics if `error` or `failure_rate` is not strictly between 0 and 1.
+    pub fn new(error: f64, failure_rate: f64) -> Self {
+        assert!(error > 0.0 && error < 1.0, "the error must be between 0 and 1, not {}", error);
+        assert!(failure_rate > 0.0 && failure_rate < 1.0, "the failure rate must be between 0 and 1, not {}", failure_rate);
+
+        let width = (E / error).ceil();
+        let depth = (1.0 / failure_rate).ln().ceil().max(1.0);
+        Self::with_size(width as u64, depth as u32)
+    }
+
+    /// Returns a sketch of `depth` rows of `width` counters.
+    ///
+    /// Panics if either is 0, or if there are more than `usize::MAX` counters.
+    pub fn with_size(width: u64, depth: u32) -> Self {
+        assert!(width > 0 && depth > 0, "a count-min sketch needs counters");
+        let len = width.checked_mul(u64::from(depth)).and_then(|len| usize::try_from(len).ok());
+        assert!(len.is_some(), "a count-min sketch cannot have {} rows of {} counters", depth, width);
+        Self { width, depth, counters: vec![0; len.unwrap()], total: 0 }
+    }
+
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Returns the sum of all the counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn add<T: Hash + ?Sized>(&mut self, item: &T, count: u64) {
+        for (row, index) in indices(item, self.depth, self.width).enumerate() {
+            let counter = &mut self.counters[row * self.width as usize + index as usize];
+            *counter = counter.saturating_add(count);
+        }
+        self.total = self.total.saturating_add(count);
+    }
+
+    /// Returns an estimate of the count of the item, which is never below the actual count.
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        indices(item, self.depth, self.width)
+            .enumerate()
+            .map(|(row, index)| self.counters[row * self.width as usize + index as usize])
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+        self.total = 0;
+    }
+
+    /// Adds the counts of `other`, which must have the same size, as if they were added to this sketch.
+    pub fn merge(&mut self, other: &CountMinSketch) -> Result<(), MergeError> {
+        if (self.width, self.depth, self.counters.len()) != (other.width, other.depth, other.counters.len()) {
+            return Err(MergeError::Incompatible);
+        }
+
+        for (counter, other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_add(*other);
+        }
+        self.total = self.total.saturating_add(other.total);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use flexbuffers::FlexbufferSerializer;
+    use serde::Serialize;
+
+    use crate::data_structures::probabilistic::MergeError;
+    use crate::serde::flexbuffer::Container;
+
+    use super::CountMinSketch;
+
+    /// Item `i` appears `1000 / (i + 1)` times, as in a skewed stream.
+    fn skewed(sketch: &mut CountMinSketch, items: impl Iterator<Item = u64>) {
+        for i in items {
+            sketch.add(&i, 1000 / (i + 1));
+        }
+    }
+
+    #[test]
+    fn sizing() {
+        let sketch = CountMinSketch::new(0.001, 0.01);
+        assert_eq!((sketch.width(), sketch.depth()), (2719, 5));
+    }
+
+    #[test]
+    fn estimates() {
+        let mut sketch = CountMinSketch::new(0.01, 0.01);
+        skewed(&mut sketch, 0..10_000);
+
+        let bound = (0.01 * sketch.total() as f64) as u64;
+        let mut within = 0;
+        for i in 0..10_000 {
+            let (actual, estimate) = (1000 / (i + 1), sketch.estimate(&i));
+            assert!(estimate >= actual);
+            if estimate - actual <= bound {
+                within += 1;
+            }
+        }
+        assert!(within >= 9_900, "{} estimates within the bound", within);
+
+        assert!(sketch.estimate("missing") <= bound);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_counters() {
+        CountMinSketch::with_size(u64::MAX, 2);
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = CountMinSketch::with_size(100, 4);
+        let mut b = CountMinSketch::with_size(100, 4);
+        a.add("x", 3);
+        b.add("x", 4);
+        b.add("y", 1);
+
+        a.merge(&b).unwrap();
+        assert_eq!(a.estimate("x"), 7);
+        assert_eq!(a.total(), 8);
+
+        assert_eq!(a.merge(&CountMinSketch::with_size(100, 5)), Err(MergeError::Incompatible));
+
+        a.clear();
+        assert_eq!(a.estimate("x"), 0);
+    }
+
+    #[test]
+    fn flexbuffer_roundtrip() {
+        let mut sketch = CountMinSketch::new(0.05, 0.05);
+        skewed(&mut sketch, 0..100);
+
+        let mut serializer = FlexbufferSerializer::new();
+        sketch.serialize(&mut serializer).unwrap();
+        let read: CountMinSketch = Container::new(serializer.view()).read().unwrap();
+
+        assert_eq!(read, sketch);
+        assert_eq!(read.estimate(&5u64), sketch.estimate(&5u64));
+    }
+
+    #[test]
+    fn flexbuffer_invalid() {
+        let mut sketch = CountMinSketch::with_size(10, 2);
+        sketch.depth = 3;
+
+        let mut serializer = FlexbufferSerializer::new();
+        sketch.serialize(&mut serializer).unwrap();
+        assert!(Container::<CountMinSketch>::new(serializer.view()).read().is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use super::{hash, InvalidError, MergeError};
+
+/// Estimates the number of distinct items of a stream, in a few kilobytes whatever the size of the stream.
+///
+/// The hash of every item picks a register, which keeps the longest run of leading zeros seen in the rest
+/// of the hashes: seeing a run of `n` zeros hints at `2^n` distinct items. The estimate combines all the
+/// registers, with a standard error of `1.04 / sqrt(2^precision)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawHyperLogLog")]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+/// An estimator as deserialized, before its fields are checked against each other.
+#[derive(Deserialize)]
+struct RawHyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl TryFrom<RawHyperLogLog> for HyperLogLog {
+    type Error = InvalidError;
+
+    fn try_from(raw: RawHyperLogLog) -> Result<Self, InvalidError> {
+        if !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&raw.precision) {
+            return Err(InvalidError::Parameters);
+        }
+        if raw.registers.len() != 1 << raw.precision {
+            return Err(InvalidError::Length);
+        }
+        Ok(Self { precision: raw.precision, registers: raw.registers })
+    }
+}
+
+impl HyperLogLog {
+    pub const MIN_PRECISION: u8 = 4;
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// Returns an estimator with `2^precision` registers.
+    ///
+    /// Panics if `precision` is not between [`MIN_PRECISION`](Self::MIN_PRECISION)
+    /// and [`MAX_PRECISION`](Self::MAX_PRECISION).
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision),
+            "the precision must be between {} and {}, not {}",
+            Self::MIN_PRECISION,
+            Self::MAX_PRECISION,
+            precision,
+        );
+        Self { precision, registers: vec![0; 1 << precision] }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns the relative standard error of the estimates.
+    pub fn standard_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let hash = hash(item);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The rank of the first set bit after the index bits, with a bit set at the end to bound it.
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Returns an estimate of the number of distinct items inserted.
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self.registers.iter().map(|rank| (-f64::from(*rank)).exp2()).sum();
+        let estimate = alpha * m * m / sum;
+
+        // Few items leave many registers empty, and counting them is more accurate.
+        let empty = self.registers.iter().filter(|rank| **rank == 0).count();
+        if estimate <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|rank| *rank == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+
+    /// Adds the items of `other`, which must have the same precision, as if they were inserted in this estimator.
+    /// Items inserted in both are only counted once.
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), MergeError> {
+        if (self.precision, self.registers.len()) != (other.precision, other.registers.len()) {
+            return Err(MergeError::Incompatible);
+        }
+
+        for (rank, other) in self.registers.iter_mut().zip(&other.registers) {
+            *rank = (*rank).max(*other);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use flexbuffers::FlexbufferSerializer;
+    use serde::Serialize;
+
+    use crate::data_structures::probabilistic::MergeError;
+    use crate::serde::flexbuffer::Container;
+
+    use super::HyperLogLog;
+
+    fn assert_close(hll: &HyperLogLog, actual: u64) {
+        let error = (hll.estimate() - actual as f64).abs() / actual as f64;
+        assert!(error < 3.0 * hll.standard_error(), "estimated {} for {}", hll.estimate(), actual);
+    }
+
+    #[test]
+    fn small() {
+        let mut hll = HyperLogLog::new(12);
+        assert!(hll.is_empty());
+        assert_eq!(hll.estimate(), 0.0);
+
+        for word in ["a", "b", "c", "a", "b", "a"] {
+            hll.insert(word);
+        }
+        assert_eq!(hll.estimate().round(), 3.0);
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut hll = HyperLogLog::new(12);
+        for i in 0..100_000u64 {
+            hll.insert(&(i % 5_000));
+        }
+        assert_close(&hll, 5_000);
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = HyperLogLog::new(14);
+        let mut b = HyperLogLog::new(14);
+        for i in 0..60_000u64 {
+            a.insert(&i);
+        }
+        for i in 40_000..100_000u64 {
+            b.insert(&i);
+        }
+
+        a.merge(&b).unwrap();
+        assert_close(&a, 100_000);
+
+        assert_eq!(a.merge(&HyperLogLog::new(10)), Err(MergeError::Incompatible));
+        a.clear();
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn flexbuffer_roundtrip() {
+        let mut hll = HyperLogLog::new(8);
+        for i in 0..1_000u64 {
+            hll.insert(&i);
+        }
+
+        let mut serializer = FlexbufferSerializer::new();
+        hll.serialize(&mut serializer).unwrap();
+        let read: HyperLogLog = Container::new(serializer.view()).read().unwrap();
+
+        assert_eq!(read, hll);
+        assert_eq!(read.estimate(), hll.estimate());
+    }
+
+    #[test]
+    fn flexbuffer_invalid() {
+        let mut hll = HyperLogLog::new(4);
+        hll.precision = 64;
+
+        let mut serializer = FlexbufferSerializer::new();
+        hll.serialize(&mut serializer).unwrap();
+        assert!(Container::<HyperLogLog>::new(serializer.view()).read().is_err());
+
+        hll.precision = 5;
+        let mut serializer = FlexbufferSerializer::new();
+        hll.serialize(&mut serializer).unwrap();
+        assert!(Container::<HyperLogLog>::new(serializer.view()).read().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_precision() {
+        HyperLogLog::new(3);
+    }
+
+    #[test]
+    fn stress_large_stream() {
+        const STRESS: u64 = 1_000_000;
+
+        let mut hll = HyperLogLog::new(14);
+        for i in 0..STRESS {
+            hll.insert(&i);
+        }
+        assert_close(&hll, STRESS);
+    }
+}
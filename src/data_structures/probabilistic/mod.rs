@@ -0,0 +1,158 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+
+pub use bloom::BloomFilter;
+pub use count_min::CountMinSketch;
+pub use hyperloglog::HyperLogLog;
+
+mod bloom;
+mod count_min;
+mod hyperloglog;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+    /// The structures were not built with the same parameters, so their contents cannot be combined.
+    Incompatible,
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Incompatible => write!(f, "cannot merge structures built with different parameters"),
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+/// Why a deserialized structure was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvalidError {
+    /// A parameter is out of the range accepted by the constructors.
+    Parameters,
+    /// The number of bits, counters or registers does not match the parameters.
+    Length,
+}
+
+impl Display for InvalidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidError::Parameters => write!(f, "parameters out of range"),
+            InvalidError::Length => write!(f, "length does not match the parameters"),
+        }
+    }
+}
+
+impl Error for InvalidError {}
+
+/// The 64-bit FNV-1a hash, finalized with the mix of SplitMix64.
+///
+/// Unlike the hasher of `std`, it is specified, so that the structures built by different processes can be merged.
+/// Integers hashed one by one are fed in little-endian byte order whatever the platform, with `usize` and `isize`
+/// widened to 64 bits. The bytes fed by `Hash` are still up to `std` otherwise: slices and arrays of integers are
+/// fed as their raw bytes, in native byte order and width, and `std` does not promise that its `Hash` impls stay
+/// the same from one release to the next.
+struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+
+    fn finish(&self) -> u64 {
+        mix(self.0)
+    }
+}
+
+/// Spreads every bit of `x` over the whole result, as FNV alone leaves the high bits poorly mixed.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+fn hash<T: Hash + ?Sized>(item: &T) -> u64 {
+    let mut hasher = StableHasher(StableHasher::OFFSET);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns `count` indices below `len` for the item, as `h1 + i * h2` from two hashes of it,
+/// which is as good as `count` independent hashes for these structures.
+fn indices<T: Hash + ?Sized>(item: &T, count: u32, len: u64) -> impl Iterator<Item = u64> {
+    let h1 = hash(item);
+    let h2 = mix(h1) | 1;
+    (0..u64::from(count)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hash, indices};
+
+    #[test]
+    fn stable_hash() {
+        // Pinned, as structures serialized by one process have to be read by the others.
+        assert_eq!(hash(&0u64), 9313164154874788883);
+        assert_eq!(hash("abc"), 913532767295458681);
+        assert_eq!(hash(&1usize), 6676229981160887125);
+        assert_eq!(hash(&-1i32), 6043530385256326746);
+        assert_eq!(hash(&1u128), 137477303321116957);
+    }
+
+    #[test]
+    fn indices_in_range() {
+        let all: Vec<u64> = indices("key", 5, 7).collect();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|i| *i < 7));
+        assert_eq!(all, indices("key", 5, 7).collect::<Vec<_>>());
+    }
+}
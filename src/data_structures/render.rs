@@ -0,0 +1,110 @@
+//! Text renderings shared by the tree structures.
+//!
+//! Both renderers walk the tree with an explicit stack, so they work on trees
+//! of any depth. `children` returns the children of a node in order, each with
+//! an optional edge label (e.g. `L` or `R` for binary trees).
+
+use std::fmt::Write;
+
+type Children<'a, N> = Vec<(Option<&'static str>, &'a N)>;
+
+/// Renders the tree as a Graphviz DOT digraph.
+pub(crate) fn dot<'a, N>(
+    root: Option<&'a N>,
+    children: impl Fn(&'a N) -> Children<'a, N>,
+    label: impl Fn(&'a N) -> String,
+) -> String {
+    let mut out = String::from("digraph {\n");
+    let mut next_id = 0;
+    // (node, parent id, label of the edge from the parent)
+    let mut stack: Vec<(&N, Option<usize>, Option<&str>)> = root.into_iter().map(|r| (r, None, None)).collect();
+
+    while let Some((node, parent, edge)) = stack.pop() {
+        let id = next_id;
+        next_id += 1;
+
+        writeln!(out, "    n{} [label=\"{}\"];", id, escape(&label(node))).unwrap();
+
+        if let Some(parent) = parent {
+            match edge {
+                None => writeln!(out, "    n{} -> n{};", parent, id).unwrap(),
+                Some(edge) => writeln!(out, "    n{} -> n{} [label=\"{}\"];", parent, id, escape(edge)).unwrap(),
+            }
+        }
+
+        for (edge, child) in children(node).into_iter().rev() {
+            stack.push((child, Some(id), edge));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Renders the tree as an indented diagram, one node per line:
+///
+/// ```text
+/// 1
+/// ├── 2
+/// │   └── 4
+/// └── 3
+/// ```
+pub(crate) fn ascii<'a, N>(
+    root: Option<&'a N>,
+    children: impl Fn(&'a N) -> Children<'a, N>,
+    label: impl Fn(&'a N) -> String,
+) -> String {
+    let mut out = String::new();
+    // (node, prefix inherited from the ancestors, label of the edge from the parent, is last child)
+    let mut stack: Vec<(&N, Option<String>, Option<&str>, bool)> = root.into_iter().map(|r| (r, None, None, true)).collect();
+
+    while let Some((node, prefix, edge, last)) = stack.pop() {
+        let child_prefix = match &prefix {
+            None => String::new(),
+            Some(prefix) => {
+                out.push_str(prefix);
+                out.push_str(if last { "└── " } else { "├── " });
+                if let Some(edge) = edge {
+                    write!(out, "{}: ", edge).unwrap();
+                }
+                format!("{}{}", prefix, if last { "    " } else { "│   " })
+            }
+        };
+
+        out.push_str(&label(node));
+        out.push('\n');
+
+        let children = children(node);
+        let count = children.len();
+        for (i, (edge, child)) in children.into_iter().enumerate().rev() {
+            stack.push((child, Some(child_prefix.clone()), edge, i + 1 == count));
+        }
+    }
+
+    out
+}
+
+fn escape(label: &str) -> String {
+    let mut result = String::with_capacity(label.len());
+
+    for c in label.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape;
+
+    #[test]
+    fn escape_label() {
+        assert_eq!(escape("a \"b\"\\\nc"), "a \\\"b\\\"\\\\\\nc");
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::mem::MaybeUninit;
+
+pub use spsc::{spsc, Consumer, Producer};
+
+mod spsc;
+
+/// What a [`RingBuffer`] does with a value pushed while it is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drops the oldest value to make room for the new one.
+    Overwrite,
+    /// Keeps the buffer as it is and gives the new value back.
+    Reject,
+}
+
+/// A queue with a fixed capacity, stored in a single allocation that is reused as values come and go.
+///
+/// The values start at `head` and wrap around the end of the storage, so they are seen as two slices
+/// by [`RingBuffer::as_slices`]: the oldest values first, then the newest ones.
+pub struct RingBuffer<T> {
+    /// Only the `len` slots from `head`, wrapping around, are initialized.
+    slots: Box<[MaybeUninit<T>]>,
+    head: usize,
+    len: usize,
+    overflow: Overflow,
+}
+
+impl<T> RingBuffer<T> {
+    /// Returns an empty buffer that holds up to `capacity` values.
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize, overflow: Overflow) -> Self {
+        assert!(capacity > 0, "a ring buffer needs room for at least one value");
+        let slots = (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+        Self { slots, head: 0, len: 0, overflow }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Adds a value after the newest one.
+    ///
+    /// When the buffer is full, it either returns the oldest value, dropped to make room for the new one,
+    /// or rejects the new value and returns it as an error, depending on its [`Overflow`].
+    pub fn push(&mut self, value: T) -> Result<Option<T>, T> {
+        if !self.is_full() {
+            let index = self.slot(self.len);
+            self.slots[index].write(value);
+            self.len += 1;
+            return Ok(None);
+        }
+
+        match self.overflow {
+            Overflow::Reject => Err(value),
+            Overflow::Overwrite => {
+                // The oldest slot becomes the newest one.
+                let oldest = std::mem::replace(&mut self.slots[self.head], MaybeUninit::new(value));
+                self.head = self.slot(1);
+                // SAFETY: the buffer is full, so every slot was initialized, the oldest one included.
+                Ok(Some(unsafe { oldest.assume_init() }))
+            }
+        }
+    }
+
+    /// Removes and returns the oldest value.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // SAFETY: the buffer is not empty, so the slot at `head` is initialized. It is left out of the values
+        // by moving `head` past it, so the value read is not read or dropped again.
+        let value = unsafe { self.slots[self.head].assume_init_read() };
+        self.head = self.slot(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Returns the value at position `index`, from the oldest one at 0.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        // SAFETY: `index` is below `len`, so its slot is initialized.
+        Some(unsafe { self.slots[self.slot(index)].assume_init_ref() })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        let index = self.slot(index);
+        // SAFETY: the position was below `len`, so its slot is initialized.
+        Some(unsafe { self.slots[index].assume_init_mut() })
+    }
+
+    /// Returns the oldest value.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the newest value.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    /// Returns the values as two slices, the second one following the first one, from the oldest value
+    /// to the newest. The second slice is empty when the values do not wrap around the end of the storage.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.ranges();
+        // SAFETY: the ranges cover exactly the `len` slots from `head`, which are initialized.
+        unsafe { (assume_init(&self.slots[first]), assume_init(&self.slots[second])) }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.ranges();
+        let (start, end) = self.slots.split_at_mut(first.start);
+        // SAFETY: `end` starts with the first range and `start` holds the second one, which cover exactly
+        // the initialized slots. Splitting the storage keeps the two slices from overlapping.
+        unsafe { (assume_init_mut(&mut end[..first.len()]), assume_init_mut(&mut start[second])) }
+    }
+
+    /// Returns an iterator over the values, from the oldest to the newest.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (first, second) = self.as_slices();
+        Iter { first: first.iter(), second: second.iter() }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// The index in the storage of the value at position `index`.
+    fn slot(&self, index: usize) -> usize {
+        (self.head + index) % self.capacity()
+    }
+
+    /// The ranges of the storage holding the values, in order.
+    fn ranges(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let end = self.head + self.len;
+        if end <= self.capacity() {
+            (self.head..end, 0..0)
+        } else {
+            (self.head..self.capacity(), 0..end - self.capacity())
+        }
+    }
+}
+
+/// # Safety
+///
+/// Every slot must be initialized.
+unsafe fn assume_init<T>(slots: &[MaybeUninit<T>]) -> &[T] {
+    // SAFETY: `MaybeUninit<T>` has the same layout as `T`, and the caller guarantees that the slots are initialized.
+    &*(slots as *const [MaybeUninit<T>] as *const [T])
+}
+
+/// # Safety
+///
+/// Every slot must be initialized.
+unsafe fn assume_init_mut<T>(slots: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: as in `assume_init`. Values written through the result are initialized, so the slots stay so.
+    &mut *(slots as *mut [MaybeUninit<T>] as *mut [T])
+}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone> Clone for RingBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new(self.capacity(), self.overflow);
+        for value in self {
+            let _ = clone.push(value.clone());
+        }
+        clone
+    }
+}
+
+impl<T: Debug> Debug for RingBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+/// Pushes the values in order. With [`Overflow::Reject`], the values that do not fit are dropped.
+impl<T> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            let _ = self.push(value);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    first: std::slice::Iter<'a, T>,
+    second: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.first.next().or_else(|| self.second.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.first.len() + self.second.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.second.next_back().or_else(|| self.first.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator that pops the values of a buffer, from the oldest to the newest.
+pub struct IntoIter<T>(RingBuffer<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for RingBuffer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::{Overflow, RingBuffer};
+
+    fn values(buffer: &RingBuffer<i32>) -> Vec<i32> {
+        buffer.iter().copied().collect()
+    }
+
+    #[test]
+    fn overwrite() {
+        let mut buffer = RingBuffer::new(3, Overflow::Overwrite);
+        assert!(buffer.is_empty());
+
+        assert_eq!(buffer.push(1), Ok(None));
+        assert_eq!(buffer.push(2), Ok(None));
+        assert_eq!(buffer.push(3), Ok(None));
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push(4), Ok(Some(1)));
+        assert_eq!(buffer.push(5), Ok(Some(2)));
+
+        assert_eq!(values(&buffer), vec![3, 4, 5]);
+        assert_eq!((buffer.front(), buffer.back()), (Some(&3), Some(&5)));
+        assert_eq!(buffer.len(), 3);
+    }
+
+    #[test]
+    fn reject() {
+        let mut buffer = RingBuffer::new(2, Overflow::Reject);
+        buffer.push(1).unwrap();
+        buffer.push(2).unwrap();
+
+        assert_eq!(buffer.push(3), Err(3));
+        assert_eq!(buffer.pop(), Some(1));
+        assert_eq!(buffer.push(3), Ok(None));
+        assert_eq!(values(&buffer), vec![2, 3]);
+    }
+
+    #[test]
+    fn pop() {
+        let mut buffer = RingBuffer::new(4, Overflow::Overwrite);
+        assert_eq!(buffer.pop(), None);
+        assert_eq!(buffer.back(), None);
+
+        buffer.extend(1..=6);
+        assert_eq!(buffer.pop(), Some(3));
+        assert_eq!(buffer.pop(), Some(4));
+        buffer.extend([7, 8]);
+
+        assert_eq!(buffer.get(0), Some(&5));
+        assert_eq!(buffer.get(3), Some(&8));
+        assert_eq!(buffer.get(4), None);
+        *buffer.get_mut(1).unwrap() = 60;
+        assert_eq!(buffer.into_iter().collect::<Vec<_>>(), vec![5, 60, 7, 8]);
+    }
+
+    #[test]
+    fn slices() {
+        let mut buffer = RingBuffer::new(5, Overflow::Overwrite);
+        buffer.extend(0..4);
+        assert_eq!(buffer.as_slices(), (&[0, 1, 2, 3][..], &[][..]));
+
+        buffer.extend(4..7);
+        assert_eq!(buffer.as_slices(), (&[2, 3, 4][..], &[5, 6][..]));
+
+        let (first, second) = buffer.as_mut_slices();
+        first[0] = 20;
+        second[1] = 60;
+        assert_eq!(values(&buffer), vec![20, 3, 4, 5, 60]);
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), vec![60, 5, 4, 3, 20]);
+        assert_eq!(buffer.iter().len(), 5);
+    }
+
+    #[test]
+    fn drops() {
+        let value = Rc::new(());
+
+        let mut buffer = RingBuffer::new(3, Overflow::Overwrite);
+        buffer.extend(std::iter::repeat_with(|| value.clone()).take(5));
+        assert_eq!(Rc::strong_count(&value), 4);
+
+        let clone = buffer.clone();
+        assert_eq!(Rc::strong_count(&value), 7);
+
+        drop(buffer.pop());
+        drop(buffer);
+        drop(clone);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn rolling_window() {
+        // The average of the last 3 samples of a metric.
+        let mut window = RingBuffer::new(3, Overflow::Overwrite);
+        let averages: Vec<i32> = [3, 6, 9, 12, 3].into_iter()
+            .map(|sample| {
+                let _ = window.push(sample);
+                window.iter().sum::<i32>() / window.len() as i32
+            })
+            .collect();
+
+        assert_eq!(averages, vec![3, 4, 6, 9, 8]);
+    }
+
+    #[test]
+    fn stress_wrap_around() {
+        const STRESS: usize = 1_000_000;
+
+        let mut buffer = RingBuffer::new(1000, Overflow::Overwrite);
+        buffer.extend(0..STRESS);
+        assert!(buffer.iter().copied().eq(STRESS - 1000..STRESS));
+
+        let mut buffer = RingBuffer::new(7, Overflow::Reject);
+        for i in 0..STRESS {
+            buffer.push(i).unwrap();
+            if i % 5 == 4 {
+                assert!((i - 4..=i).eq(std::iter::from_fn(|| buffer.pop())));
+            }
+        }
+        assert!(buffer.is_empty());
+    }
+}
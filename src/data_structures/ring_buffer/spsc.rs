@@ -0,0 +1,336 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::Arc;
+use std::{hint, thread};
+
+/// Returns the two ends of a lock-free ring buffer of `capacity` values,
+/// for one thread to push values and another one to pop them in the same order.
+///
+/// Panics if `capacity` is 0 or above `usize::MAX / 2`.
+pub fn spsc<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "a ring buffer needs room for at least one value");
+    assert!(capacity <= usize::MAX / 2, "a ring buffer holds at most usize::MAX / 2 values");
+
+    let shared = Arc::new(Shared {
+        slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        producer_alive: AtomicBool::new(true),
+        consumer_alive: AtomicBool::new(true),
+    });
+
+    (Producer { shared: shared.clone() }, Consumer { shared })
+}
+
+/// The storage shared by the producer and the consumer.
+///
+/// `head` and `tail` are the positions of the oldest value and of the next one to push, which go around
+/// `0..2 * capacity` so that a full buffer, with `capacity` values from `head` to `tail`, is told apart from
+/// an empty one, where they are equal. Position `p` is in slot `p % capacity`. Only the consumer moves `head`
+/// and only the producer moves `tail`, each one publishing the slots it is done with to the other one
+/// through a release store.
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    producer_alive: AtomicBool,
+    consumer_alive: AtomicBool,
+}
+
+// SAFETY: the storage owns its values, so moving it to another thread only moves values of type `T`.
+unsafe impl<T: Send> Send for Shared<T> {}
+// SAFETY: a slot is only ever accessed by the end that owns it at the time, as delimited by `head` and `tail`,
+// and values are moved from one end to the other without ever being shared, so `T: Send` is enough.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn len(&self) -> usize {
+        self.distance(self.head.load(Acquire), self.tail.load(Acquire))
+    }
+
+    /// The number of values from position `head` to position `tail`.
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if head <= tail {
+            tail - head
+        } else {
+            tail + 2 * self.slots.len() - head
+        }
+    }
+
+    /// The position after `position`.
+    fn next(&self, position: usize) -> usize {
+        if position + 1 == 2 * self.slots.len() {
+            0
+        } else {
+            position + 1
+        }
+    }
+
+    fn slot(&self, position: usize) -> *mut MaybeUninit<T> {
+        let capacity = self.slots.len();
+        self.slots[if position < capacity { position } else { position - capacity }].get()
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let (mut position, tail) = (*self.head.get_mut(), *self.tail.get_mut());
+        while position != tail {
+            // SAFETY: both ends are gone, so nothing else accesses the slots, and the slots from `head`
+            // to `tail` hold the values pushed and not popped, which are dropped once each.
+            unsafe { (*self.slot(position)).assume_init_drop() };
+            position = self.next(position);
+        }
+    }
+}
+
+/// The end of an [`spsc`] ring buffer that pushes values.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The end of an [`spsc`] ring buffer that pops values.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    /// Returns the number of values waiting to be popped, which may already have changed.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pushes the value if the buffer is not full, or gives it back.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let tail = self.shared.tail.load(Relaxed);
+        if self.shared.distance(self.shared.head.load(Acquire), tail) == self.capacity() {
+            return Err(value);
+        }
+
+        // SAFETY: the buffer is not full, so the slot at `tail` is outside the values and the consumer does not
+        // touch it until the release store below publishes it. Writing does not drop the popped value it held.
+        unsafe { (*self.shared.slot(tail)).write(value) };
+        self.shared.tail.store(self.shared.next(tail), Release);
+        Ok(())
+    }
+
+    /// Pushes the value, waiting for the consumer to make room if the buffer is full.
+    /// Gives the value back if the consumer is dropped, as it would then never be popped.
+    pub fn push(&mut self, mut value: T) -> Result<(), T> {
+        let mut backoff = Backoff::default();
+        loop {
+            if !self.shared.consumer_alive.load(Acquire) {
+                return Err(value);
+            }
+            match self.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(rejected) => value = rejected,
+            }
+            backoff.wait();
+        }
+    }
+}
+
+impl<T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    /// Returns the number of values waiting to be popped, which may already have changed.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pops the oldest value if there is one.
+    pub fn try_pop(&mut self) -> Option<T> {
+        let head = self.shared.head.load(Relaxed);
+        if head == self.shared.tail.load(Acquire) {
+            return None;
+        }
+
+        // SAFETY: the acquire load of `tail` saw the value written to the slot at `head`, and the producer does not
+        // touch it until the release store below hands it back. Moving `head` past it means it is not read again.
+        let value = unsafe { (*self.shared.slot(head)).assume_init_read() };
+        self.shared.head.store(self.shared.next(head), Release);
+        Some(value)
+    }
+
+    /// Pops the oldest value, waiting for the producer to push one if the buffer is empty.
+    /// Returns `None` once the producer is dropped and every value it pushed is popped.
+    pub fn pop(&mut self) -> Option<T> {
+        let mut backoff = Backoff::default();
+        loop {
+            // Checked before trying, so that the values pushed before the producer was dropped are seen.
+            let producer_alive = self.shared.producer_alive.load(Acquire);
+            if let Some(value) = self.try_pop() {
+                return Some(value);
+            }
+            if !producer_alive {
+                return None;
+            }
+            backoff.wait();
+        }
+    }
+}
+
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        self.shared.producer_alive.store(false, Release);
+    }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.shared.consumer_alive.store(false, Release);
+    }
+}
+
+/// Spins for a while, then yields the thread, so that short waits stay cheap without burning a core on long ones.
+#[derive(Default)]
+struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+
+    fn wait(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            thread::yield_now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use super::spsc;
+
+    #[test]
+    fn try_push_and_pop() {
+        let (mut producer, mut consumer) = spsc(2);
+        assert_eq!(consumer.try_pop(), None);
+
+        assert_eq!(producer.try_push(1), Ok(()));
+        assert_eq!(producer.try_push(2), Ok(()));
+        assert_eq!(producer.try_push(3), Err(3));
+        assert_eq!((producer.len(), consumer.capacity()), (2, 2));
+
+        assert_eq!(consumer.try_pop(), Some(1));
+        assert_eq!(producer.try_push(3), Ok(()));
+        assert_eq!(consumer.try_pop(), Some(2));
+        assert_eq!(consumer.try_pop(), Some(3));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn disconnection() {
+        let (mut producer, mut consumer) = spsc(4);
+        producer.push(1).unwrap();
+        producer.push(2).unwrap();
+        drop(producer);
+
+        assert_eq!(consumer.pop(), Some(1));
+        assert_eq!(consumer.pop(), Some(2));
+        assert_eq!(consumer.pop(), None);
+
+        let (mut producer, consumer) = spsc(1);
+        producer.push(1).unwrap();
+        drop(consumer);
+        assert_eq!(producer.push(2), Err(2));
+    }
+
+    struct Counted<'a>(&'a AtomicUsize);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn drop_remaining_values() {
+        let drops = AtomicUsize::new(0);
+
+        let (mut producer, mut consumer) = spsc(8);
+        for _ in 0..5 {
+            assert!(producer.try_push(Counted(&drops)).is_ok());
+        }
+        drop(consumer.try_pop());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        drop(producer);
+        drop(consumer);
+        assert_eq!(drops.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn wrap_around() {
+        // Capacities that do and do not divide the range of the positions.
+        for capacity in [3, 4] {
+            let drops = AtomicUsize::new(0);
+
+            let (mut producer, mut consumer) = spsc(capacity);
+            // The last position before they wrap around to 0.
+            producer.shared.head.store(2 * capacity - 1, Ordering::Relaxed);
+            producer.shared.tail.store(2 * capacity - 1, Ordering::Relaxed);
+            for i in 0..capacity {
+                assert!(producer.try_push((i, Counted(&drops))).is_ok());
+            }
+            assert!(producer.try_push((capacity, Counted(&drops))).is_err());
+            assert_eq!(producer.len(), capacity);
+
+            assert_eq!(consumer.try_pop().map(|(i, _)| i), Some(0));
+            assert_eq!(consumer.try_pop().map(|(i, _)| i), Some(1));
+            assert!(producer.try_push((capacity, Counted(&drops))).is_ok());
+            assert_eq!(consumer.try_pop().map(|(i, _)| i), Some(2));
+            assert_eq!(consumer.len(), capacity - 2);
+
+            drop(producer);
+            drop(consumer);
+            assert_eq!(drops.load(Ordering::Relaxed), capacity + 2);
+        }
+    }
+
+    /// The producer blocks on a small buffer while the consumer is slower,
+    /// and every value still arrives exactly once and in order.
+    #[test]
+    fn stress_threads() {
+        const STRESS: usize = 1_000_000;
+
+        let (mut producer, mut consumer) = spsc(16);
+
+        let received = thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..STRESS {
+                    producer.push(i).unwrap();
+                }
+            });
+
+            s.spawn(move || std::iter::from_fn(|| consumer.pop()).collect::<Vec<_>>()).join().unwrap()
+        });
+
+        assert!(received.into_iter().eq(0..STRESS));
+    }
+}
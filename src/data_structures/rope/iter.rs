@@ -0,0 +1,79 @@
+use std::iter::FusedIterator;
+use std::sync::Arc;
+
+use super::Node;
+
+/// An iterator over the chunks of text of a [`Rope`](super::Rope), in order.
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Chunks<'a> {
+    pub(super) fn new(root: &'a Arc<Node>) -> Self {
+        Self { stack: vec![root] }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            match self.stack.pop()? {
+                // Only an empty rope has an empty chunk.
+                Node::Leaf { text, .. } if text.is_empty() => continue,
+                Node::Leaf { text, .. } => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Chunks<'_> {}
+
+/// An iterator over the lines of a [`Rope`](super::Rope), without their newlines.
+pub struct Lines<'a> {
+    chunks: Chunks<'a>,
+    /// The rest of the current chunk.
+    pending: &'a str,
+    done: bool,
+}
+
+impl<'a> Lines<'a> {
+    pub(super) fn new(chunks: Chunks<'a>) -> Self {
+        Self { chunks, pending: "", done: false }
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.done {
+            return None;
+        }
+
+        let mut line = String::new();
+        loop {
+            if let Some(end) = self.pending.find('\n') {
+                line.push_str(&self.pending[..end]);
+                self.pending = &self.pending[end + 1..];
+                return Some(line);
+            }
+
+            line.push_str(self.pending);
+            match self.chunks.next() {
+                Some(chunk) => self.pending = chunk,
+                None => {
+                    self.done = true;
+                    return Some(line);
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Lines<'_> {}
@@ -0,0 +1,760 @@
+use std::fmt::{self, Debug, Display};
+use std::ops::{Add, Range};
+use std::sync::Arc;
+
+pub use iter::{Chunks, Lines};
+
+mod iter;
+
+/// The largest chunk of text held by a leaf, in bytes.
+const MAX_CHUNK: usize = 1024;
+
+/// A string stored as a balanced binary tree of chunks, for editing large texts.
+///
+/// Every branch knows the number of bytes, chars and newlines below it, so positions are found,
+/// and text is inserted or removed, by walking down a single path: O(log n) for a text of n chunks.
+/// Positions are counted in chars unless stated otherwise, and lines are separated by `'\n'`.
+///
+/// Nodes are shared and never modified: an edit rebuilds the path to the chunks it touches,
+/// so cloning a rope or taking a slice of it does not copy the text.
+#[derive(Clone)]
+pub struct Rope {
+    root: Arc<Node>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Info {
+    bytes: usize,
+    chars: usize,
+    newlines: usize,
+}
+
+impl Info {
+    fn of(text: &str) -> Self {
+        Self { bytes: text.len(), chars: text.chars().count(), newlines: text.bytes().filter(|b| *b == b'\n').count() }
+    }
+}
+
+impl Add for Info {
+    type Output = Info;
+
+    fn add(self, other: Info) -> Info {
+        Info { bytes: self.bytes + other.bytes, chars: self.chars + other.chars, newlines: self.newlines + other.newlines }
+    }
+}
+
+enum Node {
+    /// Only the root may be an empty leaf.
+    Leaf { text: String, info: Info },
+    /// The heights of the children differ by one at most.
+    Branch { left: Arc<Node>, right: Arc<Node>, height: usize, info: Info },
+}
+
+impl Node {
+    fn leaf(text: String) -> Arc<Node> {
+        Arc::new(Node::Leaf { info: Info::of(&text), text })
+    }
+
+    fn branch(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        let height = 1 + left.height().max(right.height());
+        let info = left.info() + right.info();
+        Arc::new(Node::Branch { left, right, height, info })
+    }
+
+    fn empty() -> Arc<Node> {
+        Node::leaf(String::new())
+    }
+
+    fn info(&self) -> Info {
+        match self {
+            Node::Leaf { info, .. } | Node::Branch { info, .. } => *info,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn children(&self) -> (Arc<Node>, Arc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (left.clone(), right.clone()),
+            Node::Leaf { .. } => unreachable!("a node taller than another one is a branch"),
+        }
+    }
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self { root: Node::empty() }
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.info().bytes
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.root.info().chars
+    }
+
+    /// Returns the number of lines, which is one more than the number of newlines.
+    pub fn len_lines(&self) -> usize {
+        self.root.info().newlines + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// Inserts `text` so that it starts at char `at`.
+    ///
+    /// Panics if `at` is greater than the length of the rope.
+    pub fn insert(&mut self, at: usize, text: &str) {
+        assert!(at <= self.len_chars(), "position {} out of bounds of a rope of {} chars", at, self.len_chars());
+
+        if let Some(root) = replace_in_leaf(&self.root, at..at, text) {
+            self.root = root;
+            return;
+        }
+        let (left, right) = split(&self.root, at);
+        self.root = concat(concat(left, Rope::from(text).root), right);
+    }
+
+    /// Removes the chars in `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn remove(&mut self, range: Range<usize>) {
+        self.check_range(&range);
+
+        if let Some(root) = replace_in_leaf(&self.root, range.clone(), "") {
+            self.root = root;
+            return;
+        }
+        let (left, rest) = split(&self.root, range.start);
+        let (_, right) = split(&rest, range.end - range.start);
+        self.root = concat(left, right);
+    }
+
+    /// Returns the chars in `range` as a rope, which shares its chunks with this one.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice(&self, range: Range<usize>) -> Rope {
+        self.check_range(&range);
+
+        let (_, rest) = split(&self.root, range.start);
+        let (slice, _) = split(&rest, range.end - range.start);
+        Rope { root: slice }
+    }
+
+    /// Splits the rope at char `at`, keeping the start and returning the end.
+    ///
+    /// Panics if `at` is greater than the length of the rope.
+    pub fn split_off(&mut self, at: usize) -> Rope {
+        assert!(at <= self.len_chars(), "position {} out of bounds of a rope of {} chars", at, self.len_chars());
+
+        let (left, right) = split(&self.root, at);
+        self.root = left;
+        Rope { root: right }
+    }
+
+    /// Adds the text of `other` at the end of the rope.
+    pub fn append(&mut self, other: Rope) {
+        self.root = concat(self.root.clone(), other.root);
+    }
+
+    /// Returns the char at position `index`.
+    pub fn char(&self, mut index: usize) -> Option<char> {
+        if index >= self.len_chars() {
+            return None;
+        }
+
+        let mut node = &self.root;
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => return text.chars().nth(index),
+                Node::Branch { left, right, .. } => {
+                    if index < left.info().chars {
+                        node = left;
+                    } else {
+                        index -= left.info().chars;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the position in bytes of char `index`.
+    ///
+    /// Panics if `index` is greater than the length of the rope.
+    pub fn char_to_byte(&self, index: usize) -> usize {
+        assert!(index <= self.len_chars(), "char {} out of bounds of a rope of {} chars", index, self.len_chars());
+
+        let (leaf, offset, before) = self.find(index, |info| info.chars);
+        before.bytes + byte_of(leaf, offset)
+    }
+
+    /// Returns the position in chars of byte `index`.
+    ///
+    /// Panics if `index` is greater than the length of the rope in bytes, or inside a char.
+    pub fn byte_to_char(&self, index: usize) -> usize {
+        assert!(index <= self.len_bytes(), "byte {} out of bounds of a rope of {} bytes", index, self.len_bytes());
+
+        let (leaf, offset, before) = self.find(index, |info| info.bytes);
+        before.chars + leaf[..offset].chars().count()
+    }
+
+    /// Returns the line of char `index`, a newline being the last char of its line.
+    ///
+    /// Panics if `index` is greater than the length of the rope.
+    pub fn char_to_line(&self, index: usize) -> usize {
+        assert!(index <= self.len_chars(), "char {} out of bounds of a rope of {} chars", index, self.len_chars());
+
+        let (leaf, offset, before) = self.find(index, |info| info.chars);
+        before.newlines + leaf.chars().take(offset).filter(|c| *c == '\n').count()
+    }
+
+    /// Returns the position of the first char of line `line`.
+    ///
+    /// Panics if `line` is not less than the number of lines.
+    pub fn line_to_char(&self, line: usize) -> usize {
+        assert!(line < self.len_lines(), "line {} out of bounds of a rope of {} lines", line, self.len_lines());
+        if line == 0 {
+            return 0;
+        }
+
+        // Looks for the newline that ends the previous line.
+        let mut newline = line;
+        let mut chars = 0;
+        let mut node = &self.root;
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => {
+                    let (position, _) = text.chars().enumerate().filter(|(_, c)| *c == '\n').nth(newline - 1).expect("the leaf has the newline");
+                    return chars + position + 1;
+                }
+                Node::Branch { left, right, .. } => {
+                    if newline <= left.info().newlines {
+                        node = left;
+                    } else {
+                        newline -= left.info().newlines;
+                        chars += left.info().chars;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the line at index `line`, without its newline.
+    pub fn line(&self, line: usize) -> Option<Rope> {
+        if line >= self.len_lines() {
+            return None;
+        }
+
+        let start = self.line_to_char(line);
+        let end = if line + 1 < self.len_lines() { self.line_to_char(line + 1) - 1 } else { self.len_chars() };
+        Some(self.slice(start..end))
+    }
+
+    /// Returns an iterator over the chunks of text, in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks::new(&self.root)
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.chunks().flat_map(str::bytes)
+    }
+
+    /// Returns an iterator over the lines, without their newlines. A rope ending with a newline ends
+    /// with an empty line, so that there are always [`Rope::len_lines`] lines.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines::new(self.chunks())
+    }
+
+    /// Returns the leaf holding position `index`, as measured by `measure`, along with the position
+    /// in the leaf and the sizes of the leaves before it.
+    fn find(&self, mut index: usize, measure: impl Fn(Info) -> usize) -> (&str, usize, Info) {
+        let mut before = Info::default();
+        let mut node = &self.root;
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => return (text, index, before),
+                Node::Branch { left, right, .. } => {
+                    if index < measure(left.info()) {
+                        node = left;
+                    } else {
+                        index -= measure(left.info());
+                        before = before + left.info();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len_chars(),
+            "range {:?} out of bounds of a rope of {} chars",
+            range,
+            self.len_chars(),
+        );
+    }
+}
+
+/// Returns the position in bytes of char `index` of `text`, or the length of `text` past its end.
+fn byte_of(text: &str, index: usize) -> usize {
+    text.char_indices().nth(index).map_or(text.len(), |(byte, _)| byte)
+}
+
+/// Replaces the chars in `range` with `text` in the leaf that holds them, when they are in a single leaf
+/// that neither outgrows a chunk nor ends up empty. This is the common case of small edits,
+/// which then leave the shape of the tree as it is.
+fn replace_in_leaf(node: &Node, range: Range<usize>, text: &str) -> Option<Arc<Node>> {
+    match node {
+        Node::Leaf { text: chunk, .. } => {
+            let (start, end) = (byte_of(chunk, range.start), byte_of(chunk, range.end));
+            let len = chunk.len() - (end - start) + text.len();
+            if len == 0 || len > MAX_CHUNK {
+                return None;
+            }
+
+            let mut replaced = String::with_capacity(len);
+            replaced.push_str(&chunk[..start]);
+            replaced.push_str(text);
+            replaced.push_str(&chunk[end..]);
+            Some(Node::leaf(replaced))
+        }
+        Node::Branch { left, right, .. } => {
+            let left_chars = left.info().chars;
+            if range.end <= left_chars {
+                Some(Node::branch(replace_in_leaf(left, range, text)?, right.clone()))
+            } else if range.start >= left_chars {
+                let range = range.start - left_chars..range.end - left_chars;
+                Some(Node::branch(left.clone(), replace_in_leaf(right, range, text)?))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Builds a balanced tree over the chunks, in order.
+fn build(chunks: &[&str]) -> Arc<Node> {
+    match chunks {
+        [] => Node::empty(),
+        [chunk] => Node::leaf(chunk.to_string()),
+        _ => {
+            let (left, right) = chunks.split_at(chunks.len() / 2);
+            Node::branch(build(left), build(right))
+        }
+    }
+}
+
+/// Splits the text before char `at`.
+fn split(node: &Arc<Node>, at: usize) -> (Arc<Node>, Arc<Node>) {
+    if at == 0 {
+        return (Node::empty(), node.clone());
+    }
+    if at >= node.info().chars {
+        return (node.clone(), Node::empty());
+    }
+
+    match &**node {
+        Node::Leaf { text, .. } => {
+            let (left, right) = text.split_at(byte_of(text, at));
+            (Node::leaf(left.to_owned()), Node::leaf(right.to_owned()))
+        }
+        Node::Branch { left, right, .. } => {
+            let left_chars = left.info().chars;
+            if at <= left_chars {
+                let (start, end) = split(left, at);
+                (start, join(end, right.clone()))
+            } else {
+                let (start, end) = split(right, at - left_chars);
+                (join(left.clone(), start), end)
+            }
+        }
+    }
+}
+
+/// Joins two trees, first trying to merge a single chunk into the closest chunk of the other tree,
+/// so that repeated small edits do not leave a trail of tiny chunks.
+fn concat(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if let Node::Leaf { text, .. } = &*right {
+        if let Some(node) = push_back(&left, text) {
+            return node;
+        }
+    }
+    if let Node::Leaf { text, .. } = &*left {
+        if let Some(node) = push_front(&right, text) {
+            return node;
+        }
+    }
+
+    join(left, right)
+}
+
+/// Appends `text` to the last chunk, if it fits.
+fn push_back(node: &Node, text: &str) -> Option<Arc<Node>> {
+    match node {
+        Node::Leaf { text: chunk, .. } => (chunk.len() + text.len() <= MAX_CHUNK).then(|| Node::leaf(chunk.clone() + text)),
+        Node::Branch { left, right, .. } => Some(Node::branch(left.clone(), push_back(right, text)?)),
+    }
+}
+
+/// Prepends `text` to the first chunk, if it fits.
+fn push_front(node: &Node, text: &str) -> Option<Arc<Node>> {
+    match node {
+        Node::Leaf { text: chunk, .. } => (chunk.len() + text.len() <= MAX_CHUNK).then(|| Node::leaf(text.to_owned() + chunk)),
+        Node::Branch { left, right, .. } => Some(Node::branch(push_front(left, text)?, right.clone())),
+    }
+}
+
+/// Joins two balanced trees into a balanced tree, in time proportional to the difference of their heights.
+fn join(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if left.info().bytes == 0 {
+        return right;
+    }
+    if right.info().bytes == 0 {
+        return left;
+    }
+
+    let (left_height, right_height) = (left.height(), right.height());
+    if left_height > right_height + 1 {
+        // Goes down the right side of the taller tree to a subtree of about the same height.
+        let (left_left, left_right) = left.children();
+        balance(left_left, join(left_right, right))
+    } else if right_height > left_height + 1 {
+        let (right_left, right_right) = right.children();
+        balance(join(left, right_left), right_right)
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+/// Returns a branch of two trees whose heights differ by two at most, rotating it if they differ by two.
+fn balance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if left.height() > right.height() + 1 {
+        let (left_left, left_right) = left.children();
+        if left_left.height() >= left_right.height() {
+            Node::branch(left_left, Node::branch(left_right, right))
+        } else {
+            let (middle_left, middle_right) = left_right.children();
+            Node::branch(Node::branch(left_left, middle_left), Node::branch(middle_right, right))
+        }
+    } else if right.height() > left.height() + 1 {
+        let (right_left, right_right) = right.children();
+        if right_right.height() >= right_left.height() {
+            Node::branch(Node::branch(left, right_left), right_right)
+        } else {
+            let (middle_left, middle_right) = right_left.children();
+            Node::branch(Node::branch(left, middle_left), Node::branch(middle_right, right_right))
+        }
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut chunks = Vec::with_capacity(text.len() / MAX_CHUNK + 1);
+        let mut rest = text;
+        while rest.len() > MAX_CHUNK {
+            let mut end = MAX_CHUNK;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            let (chunk, tail) = rest.split_at(end);
+            chunks.push(chunk);
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            chunks.push(rest);
+        }
+
+        Self { root: build(&chunks) }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Rope) -> bool {
+        self.len_bytes() == other.len_bytes() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len_bytes() == other.len() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Info, Node, Rope, MAX_CHUNK};
+
+    /// Checks the sizes kept in the branches, the balance of the tree and the size of the chunks.
+    fn check(rope: &Rope) {
+        fn walk(node: &Node, root: bool) -> (Info, usize) {
+            match node {
+                Node::Leaf { text, info } => {
+                    assert_eq!(*info, Info::of(text));
+                    assert!(text.len() <= MAX_CHUNK);
+                    assert!(root || !text.is_empty());
+                    (*info, 0)
+                }
+                Node::Branch { left, right, height, info } => {
+                    let (left_info, left_height) = walk(left, false);
+                    let (right_info, right_height) = walk(right, false);
+                    assert!(left_height.abs_diff(right_height) <= 1);
+                    assert_eq!(*height, 1 + left_height.max(right_height));
+                    assert_eq!(*info, left_info + right_info);
+                    (*info, *height)
+                }
+            }
+        }
+
+        walk(&rope.root, true);
+    }
+
+    /// A text of `lines` numbered lines, with some chars of several bytes.
+    fn text(lines: usize) -> String {
+        (0..lines).map(|i| format!("line {} — ünïcödé\n", i)).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let rope = Rope::new();
+        assert!(rope.is_empty());
+        assert_eq!((rope.len_chars(), rope.len_lines()), (0, 1));
+        assert_eq!(rope.chunks().count(), 0);
+        assert_eq!(rope.lines().collect::<Vec<_>>(), vec![""]);
+        assert_eq!(rope, "");
+    }
+
+    #[test]
+    fn from_str() {
+        let text = text(1000);
+        let rope = Rope::from(text.as_str());
+        check(&rope);
+
+        assert_eq!(rope.to_string(), text);
+        assert_eq!(rope.len_bytes(), text.len());
+        assert_eq!(rope.len_chars(), text.chars().count());
+        assert_eq!(rope.len_lines(), 1001);
+        assert!(rope.chunks().count() > text.len() / MAX_CHUNK);
+    }
+
+    #[test]
+    fn insert() {
+        let mut rope = Rope::from("Hello world");
+        rope.insert(5, ",");
+        rope.insert(12, "!");
+        rope.insert(0, "¡");
+        check(&rope);
+        assert_eq!(rope, "¡Hello, world!");
+
+        let long = text(200);
+        rope.insert(7, &long);
+        check(&rope);
+        assert_eq!(rope.to_string(), format!("¡Hello,{} world!", long));
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        Rope::from("abc").insert(4, "d");
+    }
+
+    #[test]
+    fn remove() {
+        let text = text(500);
+        let mut rope = Rope::from(text.as_str());
+        let mut expected: Vec<char> = text.chars().collect();
+
+        for (start, end) in [(0, 10), (1000, 5000), (3000, 3001), (20, 20)] {
+            rope.remove(start..end);
+            expected.drain(start..end);
+            check(&rope);
+        }
+        assert_eq!(rope.to_string(), expected.into_iter().collect::<String>());
+
+        let len = rope.len_chars();
+        rope.remove(0..len);
+        assert!(rope.is_empty());
+    }
+
+    #[test]
+    fn slice() {
+        let text = text(300);
+        let rope = Rope::from(text.as_str());
+        let chars: Vec<char> = text.chars().collect();
+
+        for (start, end) in [(0, 0), (0, 5), (100, 4000), (chars.len() - 3, chars.len())] {
+            let slice = rope.slice(start..end);
+            check(&slice);
+            assert_eq!(slice.to_string(), chars[start..end].iter().collect::<String>());
+        }
+
+        // Slices and clones share their chunks with the rope.
+        let slice = rope.slice(0..rope.len_chars());
+        assert!(std::sync::Arc::ptr_eq(&slice.root, &rope.root));
+    }
+
+    #[test]
+    fn split_off_and_append() {
+        let mut rope = Rope::from(text(100).as_str());
+        let end = rope.split_off(1234);
+        check(&rope);
+        check(&end);
+        assert_eq!(rope.len_chars(), 1234);
+
+        rope.append(end);
+        check(&rope);
+        assert_eq!(rope.to_string(), text(100));
+    }
+
+    #[test]
+    fn char_indexing() {
+        let text = text(100);
+        let rope = Rope::from(text.as_str());
+
+        for (index, (byte, c)) in text.char_indices().enumerate().step_by(7) {
+            assert_eq!(rope.char(index), Some(c));
+            assert_eq!(rope.char_to_byte(index), byte);
+            assert_eq!(rope.byte_to_char(byte), index);
+        }
+        assert_eq!(rope.char(rope.len_chars()), None);
+        assert_eq!(rope.char_to_byte(rope.len_chars()), text.len());
+    }
+
+    #[test]
+    fn line_indexing() {
+        let rope = Rope::from("ab\ncd\n\nefg");
+        assert_eq!(rope.len_lines(), 4);
+
+        let lines: Vec<usize> = (0..=rope.len_chars()).map(|i| rope.char_to_line(i)).collect();
+        assert_eq!(lines, vec![0, 0, 0, 1, 1, 1, 2, 3, 3, 3, 3]);
+
+        let starts: Vec<usize> = (0..rope.len_lines()).map(|line| rope.line_to_char(line)).collect();
+        assert_eq!(starts, vec![0, 3, 6, 7]);
+
+        assert_eq!(rope.line(1), Some(Rope::from("cd")));
+        assert_eq!(rope.line(2), Some(Rope::new()));
+        assert_eq!(rope.line(3), Some(Rope::from("efg")));
+        assert_eq!(rope.line(4), None);
+    }
+
+    #[test]
+    fn lines() {
+        let text = text(1000);
+        let rope = Rope::from(text.as_str());
+
+        let lines: Vec<String> = rope.lines().collect();
+        assert_eq!(lines.len(), rope.len_lines());
+        assert_eq!(lines.last().map(String::as_str), Some(""));
+        assert!(lines.iter().zip(text.split('\n')).all(|(line, expected)| line == expected));
+
+        assert_eq!(rope.line(500).unwrap(), "line 500 — ünïcödé");
+        assert_eq!(rope.line_to_char(500), text.split('\n').take(500).map(|line| line.chars().count() + 1).sum());
+    }
+
+    #[test]
+    fn chunks() {
+        let rope = Rope::from(text(100).as_str());
+        assert_eq!(rope.chunks().collect::<String>(), text(100));
+        assert!(rope.chunks().all(|chunk| !chunk.is_empty() && chunk.len() <= MAX_CHUNK));
+    }
+
+    #[test]
+    fn stress_typing() {
+        // Typing in the middle of a text, one char at a time. Every keystroke copies a chunk,
+        // so fewer of them are enough to fill many chunks.
+        const TYPED: usize = 20_000;
+
+        let mut rope = Rope::new();
+        for i in 0..TYPED {
+            rope.insert(rope.len_chars() / 2, if i % 50 == 49 { "\n" } else { "x" });
+        }
+
+        check(&rope);
+        assert_eq!(rope.len_chars(), TYPED);
+        assert_eq!(rope.len_lines(), TYPED / 50 + 1);
+        // Small inserts go into the existing chunks instead of adding one leaf each.
+        assert!(rope.chunks().count() < TYPED / 100);
+    }
+
+    #[test]
+    fn stress_edits() {
+        const LINES: usize = 50_000;
+
+        let text = text(LINES);
+        let mut rope = Rope::from(text.as_str());
+        let mut expected = text;
+
+        // Deterministic pseudo-random positions.
+        let mut seed = 12345usize;
+        let mut next = |bound: usize| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+
+        for i in 0..1000 {
+            let at = next(rope.len_chars());
+            let byte = rope.char_to_byte(at);
+
+            if i % 3 == 0 {
+                let end = (at + next(100)).min(rope.len_chars());
+                let end_byte = rope.char_to_byte(end);
+                rope.remove(at..end);
+                expected.replace_range(byte..end_byte, "");
+            } else {
+                rope.insert(at, "edit\n");
+                expected.insert_str(byte, "edit\n");
+            }
+        }
+
+        check(&rope);
+        assert_eq!(rope, expected.as_str());
+        assert_eq!(rope.len_lines(), expected.split('\n').count());
+    }
+}
@@ -0,0 +1,914 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+
+use super::{Node, Tree};
+
+/// A single step of an edit script between two trees, as returned by [`Tree::diff`].
+///
+/// Nodes are addressed by path: the child indices to follow from the root, as in [`Tree::get`].
+/// A path refers to the tree as it is when the edit is applied, after the previous edits of the script.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit<T> {
+    /// Inserts a subtree so that its root ends up at `path`. Inserting at `[]` fills an empty tree.
+    Insert { path: Vec<usize>, subtree: Tree<T> },
+    /// Removes the node at `path` along with its descendants.
+    Delete { path: Vec<usize> },
+    /// Replaces the value of the node at `path`.
+    Relabel { path: Vec<usize>, value: T },
+    /// Detaches the subtree at `from`, then inserts it so that its root ends up at `to`,
+    /// `to` being a path in the tree without the detached subtree.
+    Move { from: Vec<usize>, to: Vec<usize> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The path neither addresses a node nor a position where a node can be inserted.
+    InvalidPath(Vec<usize>),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidPath(path) => write!(f, "invalid path {:?}", path),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+impl<T: PartialEq + Hash + Clone> Tree<T> {
+    /// Returns a short script of edits that turns this tree into `other`, to be replayed with [`Tree::apply_patch`].
+    ///
+    /// The script is the one of the top-down tree edit distance, where relabelling a node costs one and inserting
+    /// or deleting a subtree costs its number of nodes: a kept node keeps its parent, the kept children of a node
+    /// keep their order, and the children of every pair of nodes are aligned by dynamic programming. Without moves,
+    /// this gives a script of minimal cost.
+    ///
+    /// Moves are only used for the subtrees that occur once, unchanged, in both trees. Such a subtree is either kept
+    /// in place or moved, which costs one edit, and the rest of the script is minimal given that choice: finding the
+    /// cheapest script is NP-hard once any subtree can move. The script with moves is only returned when it costs no
+    /// more than the minimal one without them, and other unchanged subtrees that the script would delete in one place
+    /// and insert in another are moved too, which only makes it cheaper.
+    ///
+    /// Identical subtrees are recognized through their hashes and skipped, but the children of two nodes are
+    /// otherwise compared pairwise, so the diff takes O(n m) time and memory in the worst case.
+    pub fn diff(&self, other: &Tree<T>) -> Vec<Edit<T>> {
+        let (old, new) = match (&self.root, &other.root) {
+            (None, None) => return Vec::new(),
+            (None, Some(_)) => return vec![Edit::Insert { path: Vec::new(), subtree: other.clone() }],
+            (Some(_), None) => return vec![Edit::Delete { path: Vec::new() }],
+            (Some(old), Some(new)) => (old, new),
+        };
+
+        // Pinning subtrees can cost more than the cheapest script without moves, which is then used instead.
+        let scripts = [true, false].map(|pin| Script::new(old, new, pin).run(new));
+        let (_, edits) = scripts.into_iter().min_by_key(|(cost, _)| *cost).expect("there are two scripts");
+        edits
+    }
+}
+
+impl<T> Tree<T> {
+    /// Applies the edits in order.
+    ///
+    /// Stops at the first edit whose path is invalid, keeping the edits applied before it.
+    pub fn apply_patch(&mut self, patch: impl IntoIterator<Item = Edit<T>>) -> Result<(), PatchError> {
+        for edit in patch {
+            self.apply(edit)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply(&mut self, edit: Edit<T>) -> Result<(), PatchError> {
+        match edit {
+            Edit::Insert { path, subtree } => {
+                self.graft_at(&path, subtree).map_err(|_| PatchError::InvalidPath(path))
+            }
+            Edit::Delete { path } if path.is_empty() && !self.is_empty() => {
+                *self = Tree::default();
+                Ok(())
+            }
+            Edit::Delete { path } => match self.detach_at(&path) {
+                Some(_) => Ok(()),
+                None => Err(PatchError::InvalidPath(path)),
+            },
+            Edit::Relabel { path, value } => match self.get_mut(&path) {
+                Some(node) => {
+                    *node.value_mut() = value;
+                    Ok(())
+                }
+                None => Err(PatchError::InvalidPath(path)),
+            },
+            Edit::Move { from, to } => {
+                let Some(subtree) = self.detach_at(&from) else {
+                    return Err(PatchError::InvalidPath(from));
+                };
+
+                // Put the subtree back where it was, so that a failed edit leaves the tree unchanged.
+                self.graft_at(&to, subtree).map_err(|subtree| {
+                    let _ = self.graft_at(&from, subtree);
+                    PatchError::InvalidPath(to)
+                })
+            }
+        }
+    }
+
+    fn detach_at(&mut self, path: &[usize]) -> Option<Tree<T>> {
+        let (&index, parent) = path.split_last()?;
+        self.get_mut(parent)?.detach(index)
+    }
+
+    fn graft_at(&mut self, path: &[usize], subtree: Tree<T>) -> Result<(), Tree<T>> {
+        match path.split_last() {
+            None if self.is_empty() => {
+                *self = subtree;
+                Ok(())
+            }
+            None => Err(subtree),
+            Some((&index, parent)) => match self.get_mut(parent) {
+                Some(node) if index <= node.children().len() => {
+                    node.graft(index, subtree);
+                    Ok(())
+                }
+                _ => Err(subtree),
+            },
+        }
+    }
+}
+
+/// The cost of keeping a node in place of a target node it cannot stand for.
+const NEVER: usize = usize::MAX / 4;
+
+/// A node of the tree being turned into the target tree, kept in an arena so that nodes
+/// can be moved around while their ids stay the same.
+struct Slot<'a, T> {
+    value: &'a T,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// The hash of the subtree in the old tree. It is not updated when nodes are moved out of the subtree,
+    /// so it only serves to rule out subtrees that are different.
+    hash: u64,
+    /// The cost of deleting the subtree, without its pinned subtrees which are moved out first.
+    deletion: usize,
+    /// Whether the subtree occurs once, unchanged, in both trees, so that it is either kept or moved.
+    pinned: bool,
+    /// Whether the node is kept, or has found its place in the target tree.
+    matched: bool,
+}
+
+/// What is known about a node of the target tree.
+struct Target {
+    hash: u64,
+    /// The cost of inserting the subtree, its pinned subtrees being moved in for one edit each.
+    insertion: usize,
+    /// Whether the subtree occurs once, unchanged, in both trees.
+    pinned: bool,
+    /// Whether some descendant is pinned, and is left out when the subtree is inserted.
+    holes: bool,
+}
+
+/// Records the edits while applying them to the arena, so that the path of every edit
+/// is computed on the tree as it is at this point of the script.
+struct Script<'a, T> {
+    slots: Vec<Slot<'a, T>>,
+    /// The nodes of the target tree, by address.
+    targets: HashMap<*const Node<T>, Target>,
+    /// The ids of the nodes of the old tree, by hash of their subtree.
+    by_hash: HashMap<u64, Vec<usize>>,
+    /// The cost of turning the subtree of a node into a target subtree, for the pairs of nodes that were compared.
+    distances: HashMap<(usize, *const Node<T>), usize>,
+    /// The node in place of each target node, and whether its whole subtree is in place.
+    kept: HashMap<*const Node<T>, (usize, bool)>,
+    edits: Vec<Edit<T>>,
+    /// The cost of the edits so far.
+    cost: usize,
+}
+
+impl<'a, T: PartialEq + Hash + Clone> Script<'a, T> {
+    /// Prepares the script from `root` to `target`, pinning the subtrees that occur once in both if `pin` is set.
+    fn new(root: &'a Node<T>, target: &'a Node<T>, pin: bool) -> Self {
+        let mut script = Self {
+            slots: Vec::new(),
+            targets: HashMap::new(),
+            by_hash: HashMap::new(),
+            distances: HashMap::new(),
+            kept: HashMap::new(),
+            edits: Vec::new(),
+            cost: 0,
+        };
+        script.push_subtree(root, None, false);
+
+        // Ids are given in pre-order, so the children of a node are hashed before it.
+        for id in (0..script.slots.len()).rev() {
+            let slot = &script.slots[id];
+            let children = slot.children.iter().map(|&child| script.slots[child].hash);
+            let hash = hash(slot.value, children);
+            script.slots[id].hash = hash;
+            script.by_hash.entry(hash).or_default().push(id);
+        }
+
+        // The target nodes in post-order, with their parents.
+        let mut order: Vec<(&'a Node<T>, Option<&'a Node<T>>)> = Vec::new();
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        let mut stack = vec![(target, None, false)];
+        while let Some((node, parent, visited)) = stack.pop() {
+            if visited {
+                let children = node.children().iter().map(|child| script.targets[&(child as *const Node<T>)].hash);
+                let hash = hash(&node.value, children);
+                script.targets.insert(node, Target { hash, insertion: 0, pinned: false, holes: false });
+                *counts.entry(hash).or_default() += 1;
+                order.push((node, parent));
+            } else {
+                stack.push((node, parent, true));
+                stack.extend(node.children().iter().map(|child| (child, Some(node), false)));
+            }
+        }
+
+        // Only the largest unique subtrees are pinned: the parents of a pair that is unchanged as well
+        // are pinned instead, which the hashes of the parents are enough to tell.
+        for &(node, parent) in order.iter().filter(|_| pin) {
+            let Some(parent) = parent else {
+                continue;
+            };
+            let hash = script.targets[&(node as *const Node<T>)].hash;
+            let (Some(&[id]), Some(1)) = (script.by_hash.get(&hash).map(Vec::as_slice), counts.get(&hash)) else {
+                continue;
+            };
+            let Some(old_parent) = script.slots[id].parent else {
+                continue;
+            };
+
+            if script.slots[old_parent].hash != script.targets[&(parent as *const Node<T>)].hash && script.same(id, node) {
+                script.slots[id].pinned = true;
+                script.targets.get_mut(&(node as *const Node<T>)).unwrap().pinned = true;
+            }
+        }
+
+        for id in (0..script.slots.len()).rev() {
+            let slot = &script.slots[id];
+            let children: usize = slot.children.iter().map(|&child| script.slots[child].deletion).sum();
+            script.slots[id].deletion = if slot.pinned { 0 } else { 1 + children };
+        }
+
+        for &(node, _) in &order {
+            let children = node.children().iter().map(|child| &script.targets[&(child as *const Node<T>)]);
+            let (insertion, holes) = children.fold((1, false), |(insertion, holes), child| {
+                (insertion + child.insertion, holes || child.pinned || child.holes)
+            });
+
+            let target = script.targets.get_mut(&(node as *const Node<T>)).unwrap();
+            target.insertion = if target.pinned { 1 } else { insertion };
+            target.holes = holes;
+        }
+
+        script
+    }
+
+    /// Returns the cost of the script that turns the old tree into `target`, and its edits.
+    fn run(mut self, target: &'a Node<T>) -> (usize, Vec<Edit<T>>) {
+        if self.same(0, target) {
+            return (0, Vec::new());
+        }
+
+        self.distance(0, target);
+        self.keep(0, target);
+        if self.slots[0].value != &*target.value {
+            self.relabel(0, &target.value);
+        }
+
+        let mut stack = vec![(target, 0)];
+        while let Some((target, id)) = stack.pop() {
+            let pending = self.match_children(id, target);
+            stack.extend(pending);
+        }
+
+        self.delete_unmatched();
+        (self.cost, self.edits)
+    }
+
+    /// Adds the nodes of the subtree to the arena, in pre-order, and returns the id of its root.
+    ///
+    /// The old tree is added unmatched. A target subtree is added as inserted, without its pinned subtrees,
+    /// and its nodes are recorded in place of the target ones.
+    fn push_subtree(&mut self, root: &'a Node<T>, parent: Option<usize>, inserted: bool) -> usize {
+        let first = self.slots.len();
+        let mut stack = vec![(root, parent)];
+
+        while let Some((node, parent)) = stack.pop() {
+            let id = self.slots.len();
+            if let Some(parent) = parent {
+                if id != first {
+                    self.slots[parent].children.push(id);
+                }
+            }
+
+            self.slots.push(Slot {
+                value: &node.value,
+                parent,
+                children: Vec::new(),
+                hash: 0,
+                deletion: 0,
+                pinned: false,
+                matched: inserted,
+            });
+
+            let mut children: Vec<&'a Node<T>> = node.children().iter().collect();
+            if inserted {
+                self.kept.insert(node, (id, !self.targets[&(node as *const Node<T>)].holes));
+                children.retain(|&child| !self.targets[&(child as *const Node<T>)].pinned);
+            }
+            stack.extend(children.into_iter().rev().map(|child| (child, Some(id))));
+        }
+
+        first
+    }
+
+    /// Returns a copy of the target subtree without its pinned subtrees, built without recursion.
+    fn pruned(&self, root: &Node<T>) -> Tree<T> {
+        let kept = |node: &&Node<T>| !self.targets[&(*node as *const Node<T>)].pinned;
+        let mut stack = vec![(root, false)];
+        // The copies of the children of the nodes being copied, in order.
+        let mut copies: Vec<Node<T>> = Vec::new();
+
+        while let Some((node, visited)) = stack.pop() {
+            if visited {
+                let children = copies.split_off(copies.len() - node.children().iter().filter(kept).count());
+                let children = (!children.is_empty()).then_some(children);
+                copies.push(Node { value: node.value.clone(), children });
+            } else {
+                stack.push((node, true));
+                stack.extend(node.children().iter().filter(kept).rev().map(|child| (child, false)));
+            }
+        }
+
+        Tree::with_root(copies.pop())
+    }
+
+    /// Computes the cost of turning the subtree of the node into `target`, and first the costs
+    /// of all the pairs of descendants it depends on, bottom-up.
+    fn distance(&mut self, id: usize, target: &'a Node<T>) {
+        let mut stack = vec![(id, target, false)];
+
+        while let Some((id, target, visited)) = stack.pop() {
+            if !visited && self.distances.contains_key(&(id, target as *const Node<T>)) {
+                continue;
+            }
+
+            let children = self.slots[id].children.clone();
+            let expected = target.children();
+            let (prefix, suffix) = self.common_ends(&children, expected);
+            let (children, expected) = (&children[prefix..children.len() - suffix], &expected[prefix..expected.len() - suffix]);
+
+            if visited {
+                let relabel = usize::from(self.slots[id].value != &*target.value);
+                let distance = relabel + self.align(children, expected)[0];
+                self.distances.insert((id, target), distance);
+            } else if self.same(id, target) {
+                self.distances.insert((id, target), 0);
+            } else if self.slots[id].pinned || self.targets[&(target as *const Node<T>)].pinned {
+                // A pinned subtree only stands for the same one.
+                self.distances.insert((id, target), NEVER);
+            } else {
+                stack.push((id, target, true));
+                for &child in children {
+                    stack.extend(expected.iter().map(|node| (child, node, false)));
+                }
+            }
+        }
+    }
+
+    /// Records the nodes kept by the cheapest script, from the root down. Deleting and inserting a subtree
+    /// is preferred to keeping a node when it costs as much, so that the subtree may be moved instead.
+    fn keep(&mut self, id: usize, target: &'a Node<T>) {
+        let mut stack = vec![(id, target, false)];
+
+        while let Some((id, target, complete)) = stack.pop() {
+            self.kept.insert(target, (id, complete));
+            if complete {
+                self.mark_subtree(id);
+                continue;
+            }
+            self.slots[id].matched = true;
+
+            let children = self.slots[id].children.clone();
+            let expected = target.children();
+            let (prefix, suffix) = self.common_ends(&children, expected);
+            let ends = children.iter().zip(expected).take(prefix)
+                .chain(children.iter().rev().zip(expected.iter().rev()).take(suffix));
+            stack.extend(ends.map(|(&child, node)| (child, node, true)));
+
+            let (children, expected) = (&children[prefix..children.len() - suffix], &expected[prefix..expected.len() - suffix]);
+            let table = self.align(children, expected);
+            let width = expected.len() + 1;
+            let (mut i, mut j) = (0, 0);
+
+            while i < children.len() && j < expected.len() {
+                let cost = table[i * width + j];
+                if cost == table[(i + 1) * width + j] + self.slots[children[i]].deletion {
+                    i += 1;
+                } else if cost == table[i * width + j + 1] + self.targets[&(&expected[j] as *const Node<T>)].insertion {
+                    j += 1;
+                } else {
+                    stack.push((children[i], &expected[j], self.same(children[i], &expected[j])));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the lengths of the common prefix and of the common suffix of identical subtrees, which the
+    /// cheapest script always keeps as they are, since inserting or deleting one costs something.
+    fn common_ends(&self, children: &[usize], expected: &[Node<T>]) -> (usize, usize) {
+        let len = children.len().min(expected.len());
+        let prefix = (0..len).take_while(|&i| self.same(children[i], &expected[i])).count();
+        let suffix = (1..=len - prefix)
+            .take_while(|&k| self.same(children[children.len() - k], &expected[expected.len() - k]))
+            .count();
+
+        (prefix, suffix)
+    }
+
+    /// Aligns the children with the expected ones, whose distances are already computed: the cell `i * (m + 1) + j`
+    /// of the returned table is the cost of turning `children[i..]` into `expected[j..]`.
+    fn align(&self, children: &[usize], expected: &[Node<T>]) -> Vec<usize> {
+        let (n, m) = (children.len(), expected.len());
+        let width = m + 1;
+        let mut table = vec![0; (n + 1) * width];
+
+        for i in (0..=n).rev() {
+            for j in (0..=m).rev() {
+                let delete = || table[(i + 1) * width + j] + self.slots[children[i]].deletion;
+                let insert = || table[i * width + j + 1] + self.targets[&(&expected[j] as *const Node<T>)].insertion;
+
+                table[i * width + j] = match (i < n, j < m) {
+                    (false, false) => 0,
+                    (true, false) => delete(),
+                    (false, true) => insert(),
+                    (true, true) => {
+                        let keep = self.distances[&(children[i], &expected[j] as *const Node<T>)]
+                            + table[(i + 1) * width + j + 1];
+                        keep.min(delete()).min(insert())
+                    }
+                };
+            }
+        }
+
+        table
+    }
+
+    /// Puts the children of `target` under `parent`, as the kept nodes or as moved or inserted subtrees,
+    /// and returns the pairs of nodes whose own children still have to be put in place.
+    fn match_children(&mut self, parent: usize, target: &'a Node<T>) -> Vec<(&'a Node<T>, usize)> {
+        let expected = target.children();
+        let kept: Vec<Option<(usize, bool)>> =
+            expected.iter().map(|child| self.kept.get(&(child as *const Node<T>)).copied()).collect();
+
+        let mut pending = Vec::new();
+        let mut previous = None;
+
+        for (index, child) in expected.iter().enumerate() {
+            let (id, complete) = match kept[index] {
+                Some((id, complete)) => {
+                    if self.slots[id].value != &*child.value {
+                        self.relabel(id, &child.value);
+                    }
+                    (id, complete)
+                }
+                None => {
+                    let next = kept[index + 1..].iter().flatten().map(|&(id, _)| id).next();
+                    self.place(parent, previous, next, child)
+                }
+            };
+
+            if !complete {
+                pending.push((child, id));
+            }
+            previous = Some(id);
+        }
+
+        pending
+    }
+
+    /// Moves a subtree that is not kept and is the same as `target` right after `previous` among the children
+    /// of `parent`, `next` being kept in place, or inserts `target` there if there is none.
+    /// Returns its id and whether its whole subtree is in place.
+    fn place(
+        &mut self,
+        parent: usize,
+        previous: Option<usize>,
+        next: Option<usize>,
+        target: &'a Node<T>,
+    ) -> (usize, bool) {
+        if let Some(id) = self.find_same(parent, target) {
+            self.put(id, parent, previous, next);
+            self.mark_subtree(id);
+            return (id, true);
+        }
+
+        let index = self.index_after(parent, previous);
+        let mut path = self.path(parent);
+        path.push(index);
+
+        let id = self.push_subtree(target, Some(parent), true);
+        self.slots[parent].children.insert(index, id);
+        self.edits.push(Edit::Insert { path, subtree: self.pruned(target) });
+        self.cost += self.slots.len() - id;
+
+        (id, !self.targets[&(target as *const Node<T>)].holes)
+    }
+
+    /// Returns a node that is not kept and whose subtree is the same as `target`, preferably a child of `parent`,
+    /// then the root of a deleted subtree, as moving it saves both its insertion and its deletion.
+    fn find_same(&self, parent: usize, target: &Node<T>) -> Option<usize> {
+        let candidates = self.by_hash.get(&self.targets[&(target as *const Node<T>)].hash)?;
+
+        candidates.iter().copied()
+            .filter(|&id| !self.slots[id].matched && self.same(id, target))
+            .min_by_key(|&id| {
+                let old_parent = self.slots[id].parent.expect("the root is always kept");
+                (old_parent != parent, !self.slots[old_parent].matched)
+            })
+    }
+
+    fn relabel(&mut self, id: usize, value: &'a T) {
+        self.slots[id].value = value;
+        self.edits.push(Edit::Relabel { path: self.path(id), value: value.clone() });
+        self.cost += 1;
+    }
+
+    /// Moves the node right after `previous` among the children of `parent`,
+    /// unless only unmatched nodes already stand between `previous` and the node.
+    fn put(&mut self, id: usize, parent: usize, previous: Option<usize>, next: Option<usize>) {
+        let old_parent = self.slots[id].parent.expect("the root is never moved");
+        if old_parent == parent && self.is_between(id, previous, next) {
+            return;
+        }
+
+        let from = self.path(id);
+        self.slots[old_parent].children.retain(|&child| child != id);
+
+        let index = self.index_after(parent, previous);
+        let mut to = self.path(parent);
+        to.push(index);
+        self.slots[parent].children.insert(index, id);
+        self.slots[id].parent = Some(parent);
+
+        self.edits.push(Edit::Move { from, to });
+        self.cost += 1;
+    }
+
+    /// Whether the node stands after `previous` and before `next`, which are its siblings.
+    fn is_between(&self, id: usize, previous: Option<usize>, next: Option<usize>) -> bool {
+        let index = self.index(id);
+        previous.is_none_or(|previous| self.index(previous) < index) && next.is_none_or(|next| index < self.index(next))
+    }
+
+    /// Returns the index right after `previous` among the children of `parent`, or 0 if there is no `previous`.
+    fn index_after(&self, parent: usize, previous: Option<usize>) -> usize {
+        previous.map_or(0, |previous| {
+            debug_assert_eq!(self.slots[previous].parent, Some(parent));
+            self.index(previous) + 1
+        })
+    }
+
+    /// Returns the index of the node among the children of its parent.
+    fn index(&self, id: usize) -> usize {
+        let parent = self.slots[id].parent.expect("the root has no siblings");
+        let index = self.slots[parent].children.iter().position(|&child| child == id);
+        index.expect("a node is among the children of its parent")
+    }
+
+    fn delete_unmatched(&mut self) {
+        for id in 0..self.slots.len() {
+            let Some(parent) = self.slots[id].parent else {
+                continue;
+            };
+
+            // Only the highest unmatched nodes are deleted, along with their descendants.
+            if !self.slots[id].matched && self.slots[parent].matched {
+                self.edits.push(Edit::Delete { path: self.path(id) });
+                self.cost += self.size(id);
+                self.slots[parent].children.retain(|&child| child != id);
+            }
+        }
+    }
+
+    /// Returns the number of nodes of the subtree, as it is at this point of the script.
+    fn size(&self, id: usize) -> usize {
+        let mut size = 0;
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            size += 1;
+            stack.extend_from_slice(&self.slots[id].children);
+        }
+
+        size
+    }
+
+    fn mark_subtree(&mut self, id: usize) {
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            self.slots[id].matched = true;
+            stack.extend_from_slice(&self.slots[id].children);
+        }
+    }
+
+    /// Whether the subtree of the node is the same as `target`.
+    fn same(&self, id: usize, target: &Node<T>) -> bool {
+        if self.slots[id].hash != self.targets[&(target as *const Node<T>)].hash {
+            return false;
+        }
+
+        let mut stack = vec![(id, target)];
+
+        while let Some((id, node)) = stack.pop() {
+            let slot = &self.slots[id];
+            if slot.value != &*node.value || slot.children.len() != node.children().len() {
+                return false;
+            }
+            stack.extend(slot.children.iter().copied().zip(node.children()));
+        }
+
+        true
+    }
+
+    fn path(&self, mut id: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+
+        while let Some(parent) = self.slots[id].parent {
+            path.push(self.index(id));
+            id = parent;
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+fn hash<T: Hash>(value: &T, children: impl Iterator<Item = u64>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    for child in children {
+        child.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_structures::tree::test::depth_4;
+    use crate::data_structures::tree::Tree;
+
+    use super::{Edit, PatchError};
+
+    fn edit(mut tree: Tree<i32>, f: impl FnOnce(&mut Tree<i32>)) -> Tree<i32> {
+        f(&mut tree);
+        tree
+    }
+
+    /// Checks that the diff turns `old` into `new`, and returns it.
+    fn round_trip(old: &Tree<i32>, new: &Tree<i32>) -> Vec<Edit<i32>> {
+        let patch = old.diff(new);
+
+        let mut patched = old.clone();
+        patched.apply_patch(patch.clone()).unwrap();
+        assert_eq!(&patched, new, "{:?}", patch);
+
+        patch
+    }
+
+    #[test]
+    fn same_tree() {
+        assert!(round_trip(&depth_4(), &depth_4()).is_empty());
+        assert!(round_trip(&Tree::default(), &Tree::default()).is_empty());
+    }
+
+    #[test]
+    fn empty_trees() {
+        let patch = round_trip(&Tree::default(), &depth_4());
+        assert_eq!(patch, vec![Edit::Insert { path: vec![], subtree: depth_4() }]);
+
+        let patch = round_trip(&depth_4(), &Tree::default());
+        assert_eq!(patch, vec![Edit::Delete { path: vec![] }]);
+    }
+
+    #[test]
+    fn relabel() {
+        let new = edit(depth_4(), |tree| *tree.get_mut(&[0, 1]).unwrap().value_mut() = 50);
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Relabel { path: vec![0, 1], value: 50 }]);
+
+        let new = edit(depth_4(), |tree| *tree.root_mut().unwrap().value_mut() = 10);
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Relabel { path: vec![], value: 10 }]);
+    }
+
+    #[test]
+    fn insert() {
+        let new = edit(depth_4(), |tree| tree.get_mut(&[0, 0]).unwrap().insert_child(0, 8));
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Insert { path: vec![0, 0, 0], subtree: Tree::new(8) }]);
+
+        let subtree: Tree<i32> = [8, 9, 10].into_iter().collect();
+        let new = edit(depth_4(), |tree| tree.root_mut().unwrap().graft(1, subtree.clone()));
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Insert { path: vec![1], subtree }]);
+    }
+
+    #[test]
+    fn delete() {
+        let new = edit(depth_4(), |tree| drop(tree.root_mut().unwrap().detach(0)));
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Delete { path: vec![0] }]);
+
+        let new = edit(depth_4(), |tree| drop(tree.get_mut(&[0]).unwrap().detach(1)));
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Delete { path: vec![0, 1] }]);
+    }
+
+    #[test]
+    fn move_sibling() {
+        let new = edit(depth_4(), |tree| {
+            let root = tree.root_mut().unwrap();
+            let two = root.detach(0).unwrap();
+            root.graft(1, two);
+        });
+
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Move { from: vec![0], to: vec![1] }]);
+
+        let old: Tree<i32> = (0..6).collect();
+        let new: Tree<i32> = [0, 5, 1, 2, 3, 4].into_iter().collect();
+        assert_eq!(round_trip(&old, &new), vec![Edit::Move { from: vec![4], to: vec![0] }]);
+    }
+
+    #[test]
+    fn move_to_other_parent() {
+        let new = edit(depth_4(), |tree| {
+            let four = tree.get_mut(&[0]).unwrap().detach(0).unwrap();
+            tree.get_mut(&[1, 0]).unwrap().graft(0, four);
+        });
+
+        assert_eq!(round_trip(&depth_4(), &new), vec![Edit::Move { from: vec![0, 0], to: vec![1, 0, 0] }]);
+    }
+
+    #[test]
+    fn several_edits() {
+        let new = edit(depth_4(), |tree| {
+            let four = tree.get_mut(&[0]).unwrap().detach(0).unwrap();
+            tree.root_mut().unwrap().graft(2, four);
+            *tree.get_mut(&[1, 0]).unwrap().value_mut() = 60;
+            tree.get_mut(&[0, 0]).unwrap().insert_child(0, 8);
+            tree.root_mut().unwrap().insert_child(0, 9);
+        });
+
+        assert_eq!(round_trip(&depth_4(), &new).len(), 4);
+        assert_eq!(round_trip(&new, &depth_4()).len(), 4);
+    }
+
+    #[test]
+    fn unrelated_trees() {
+        let old: Tree<i32> = (0..10).collect();
+        let new = depth_4();
+
+        round_trip(&old, &new);
+        round_trip(&new, &old);
+    }
+
+    #[test]
+    fn minimal() {
+        // Relabelling both leaves is shorter than deleting and inserting them.
+        let old = Tree::from_sexpr("(0 (1 2) (3 4))").unwrap();
+        let new = Tree::from_sexpr("(0 (1 5) (3 6))").unwrap();
+        assert_eq!(round_trip(&old, &new).len(), 2);
+
+        // Reversing the children takes one move per child but the one kept in place.
+        let old: Tree<i32> = (0..6).collect();
+        let new: Tree<i32> = [0, 5, 4, 3, 2, 1].into_iter().collect();
+        let patch = round_trip(&old, &new);
+        assert_eq!(patch.len(), 4);
+        assert!(patch.iter().all(|edit| matches!(edit, Edit::Move { .. })));
+
+        // Relabelling a node keeps its children, where inserting it would insert them again.
+        let old = Tree::from_sexpr("(0 (1 2 3 4))").unwrap();
+        let new = Tree::from_sexpr("(0 (9 2 3 4) 1)").unwrap();
+        assert_eq!(round_trip(&old, &new).len(), 2);
+    }
+
+    /// Builds a tree of `size` nodes valued from `0` to `range`, each node being inserted
+    /// at a position chosen by a linear congruential generator.
+    fn random(seed: u64, size: usize, range: i32) -> Tree<i32> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as usize
+        };
+
+        let mut tree = Tree::new(0);
+        for _ in 1..size {
+            let parents = all_paths(&tree);
+            let node = tree.get_mut(&parents[next() % parents.len()]).unwrap();
+            let index = next() % (node.children().len() + 1);
+            node.insert_child(index, (next() % range as usize) as i32);
+        }
+
+        tree
+    }
+
+    fn all_paths(tree: &Tree<i32>) -> Vec<Vec<usize>> {
+        let mut paths = Vec::new();
+        let mut stack = vec![(tree.root().unwrap(), vec![])];
+
+        while let Some((node, path)) = stack.pop() {
+            for (index, child) in node.children().iter().enumerate() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((child, child_path));
+            }
+            paths.push(path);
+        }
+
+        paths
+    }
+
+    #[test]
+    fn random_round_trips() {
+        for seed in 0..50 {
+            let old = random(seed, 30, 8);
+            let new = random(seed + 1000, 30, 8);
+
+            round_trip(&old, &new);
+            round_trip(&new, &old);
+        }
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let mut tree = depth_4();
+
+        assert_eq!(tree.apply_patch(vec![Edit::Delete { path: vec![2] }]), Err(PatchError::InvalidPath(vec![2])));
+        assert_eq!(tree.apply_patch(vec![Edit::Relabel { path: vec![0, 2], value: 0 }]), Err(PatchError::InvalidPath(vec![0, 2])));
+        assert_eq!(tree.apply_patch(vec![Edit::Insert { path: vec![], subtree: Tree::new(0) }]), Err(PatchError::InvalidPath(vec![])));
+        assert_eq!(tree.apply_patch(vec![Edit::Insert { path: vec![3], subtree: Tree::new(0) }]), Err(PatchError::InvalidPath(vec![3])));
+        assert_eq!(tree.apply_patch(vec![Edit::Move { from: vec![], to: vec![0] }]), Err(PatchError::InvalidPath(vec![])));
+
+        // The subtree is put back when it cannot be moved.
+        let patch = vec![Edit::Move { from: vec![0], to: vec![0, 2] }];
+        assert_eq!(tree.apply_patch(patch), Err(PatchError::InvalidPath(vec![0, 2])));
+        assert_eq!(tree, depth_4());
+
+        assert_eq!(PatchError::InvalidPath(vec![0, 2]).to_string(), "invalid path [0, 2]");
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let mut tree = depth_4();
+        let patch = vec![
+            Edit::Relabel { path: vec![], value: 10 },
+            Edit::Delete { path: vec![5] },
+            Edit::Relabel { path: vec![0], value: 20 },
+        ];
+
+        assert!(tree.apply_patch(patch).is_err());
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![10, 2, 4, 7, 5, 3, 6]);
+    }
+
+    #[test]
+    fn stress_wide() {
+        const STRESS: i32 = 500;
+
+        let old: Tree<i32> = (0..STRESS).collect();
+        let mut new = old.clone();
+        let root = new.root_mut().unwrap();
+        root.remove_child(10);
+        root.insert_child(STRESS as usize / 2, -1);
+
+        let patch = round_trip(&old, &new);
+        assert_eq!(patch, vec![
+            Edit::Insert { path: vec![STRESS as usize / 2 + 1], subtree: Tree::new(-1) },
+            Edit::Delete { path: vec![10] },
+        ]);
+    }
+
+    #[test]
+    fn stress_deep() {
+        const STRESS: usize = 20_000;
+
+        let mut old = Tree::new(0);
+        let mut node = old.root_mut().unwrap();
+        for value in 1..STRESS as i32 {
+            node.add(value);
+            node = node.child_mut(0).unwrap();
+        }
+
+        let mut new = old.clone();
+        let last = new.find_mut(|v| *v == STRESS as i32 - 1).unwrap();
+        *last.value_mut() = -1;
+        last.add(-2);
+
+        let patch = old.diff(&new);
+        assert_eq!(patch.len(), 2);
+
+        old.apply_patch(patch).unwrap();
+        assert!(old == new);
+    }
+}
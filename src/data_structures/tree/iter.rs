@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use super::Node;
+
+/// Borrowing pre-order iterator over a [`Tree`](super::Tree): each node before its children.
+pub struct PreOrder<'a, T> {
+    inner: WithDepth<'a, T>,
+}
+
+impl<'a, T> PreOrder<'a, T> {
+    pub(super) fn new(root: Option<&'a Node<T>>) -> Self {
+        Self { inner: WithDepth::new(root) }
+    }
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+}
+
+/// Borrowing pre-order iterator yielding each value along with its depth, the root being at depth 0.
+pub struct WithDepth<'a, T> {
+    stack: Vec<(usize, &'a Node<T>)>,
+}
+
+impl<'a, T> WithDepth<'a, T> {
+    pub(super) fn new(root: Option<&'a Node<T>>) -> Self {
+        Self { stack: root.into_iter().map(|r| (0, r)).collect() }
+    }
+}
+
+impl<'a, T> Iterator for WithDepth<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+
+        if let Some(children) = &node.children {
+            self.stack.extend(children.iter().rev().map(|c| (depth + 1, c)));
+        }
+
+        Some((depth, &node.value))
+    }
+}
+
+/// Borrowing post-order iterator over a [`Tree`](super::Tree): each node after its children.
+pub struct PostOrder<'a, T> {
+    /// The nodes on the path from the root, with the index of their next child to visit.
+    stack: Vec<(&'a Node<T>, usize)>,
+}
+
+impl<'a, T> PostOrder<'a, T> {
+    pub(super) fn new(root: Option<&'a Node<T>>) -> Self {
+        Self { stack: root.into_iter().map(|r| (r, 0)).collect() }
+    }
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, next_child) = self.stack.last_mut()?;
+            let node = *node;
+
+            match node.children.as_ref().and_then(|c| c.get(*next_child)) {
+                Some(child) => {
+                    *next_child += 1;
+                    self.stack.push((child, 0));
+                }
+                None => {
+                    self.stack.pop();
+                    return Some(&node.value);
+                }
+            }
+        }
+    }
+}
+
+/// Borrowing breadth-first iterator over a [`Tree`](super::Tree): level by level, from the root.
+pub struct BreadthFirst<'a, T> {
+    queue: VecDeque<&'a Node<T>>,
+}
+
+impl<'a, T> BreadthFirst<'a, T> {
+    pub(super) fn new(root: Option<&'a Node<T>>) -> Self {
+        Self { queue: root.into_iter().collect() }
+    }
+}
+
+impl<'a, T> Iterator for BreadthFirst<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+
+        if let Some(children) = &node.children {
+            self.queue.extend(children.iter());
+        }
+
+        Some(&node.value)
+    }
+}
+
+/// Borrowing pre-order iterator yielding, for each node, the values on the path
+/// from the root to that node, both included.
+pub struct Paths<'a, T> {
+    /// The nodes on the path from the root, with the index of their next child to visit.
+    stack: Vec<(&'a Node<T>, usize)>,
+    root: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Paths<'a, T> {
+    pub(super) fn new(root: Option<&'a Node<T>>) -> Self {
+        Self { stack: Vec::new(), root }
+    }
+
+    fn path(&self) -> Vec<&'a T> {
+        self.stack.iter().map(|(node, _)| node.value.as_ref()).collect()
+    }
+}
+
+impl<'a, T> Iterator for Paths<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            self.stack.push((root, 0));
+            return Some(self.path());
+        }
+
+        loop {
+            let (node, next_child) = self.stack.last_mut()?;
+
+            match node.children.as_ref().and_then(|c| c.get(*next_child)) {
+                Some(child) => {
+                    *next_child += 1;
+                    self.stack.push((child, 0));
+                    return Some(self.path());
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
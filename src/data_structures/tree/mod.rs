@@ -0,0 +1,1080 @@
+use std::alloc::Layout;
+use std::fmt::{self, Debug};
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+use crate::traits::functor::Functor;
+
+use super::render;
+
+pub use diff::{Edit, PatchError};
+pub use iter::{BreadthFirst, Paths, PostOrder, PreOrder, WithDepth};
+pub use parse::ParseError;
+pub use zipper::Zipper;
+
+mod diff;
+mod iter;
+mod parse;
+mod zipper;
+
+pub struct Node<T> {
+    value: Box<T>,
+    children: Option<Vec<Node<T>>>
+}
+
+impl<T> Node<T> {
+    pub fn new(value: T) -> Self {
+        Self { value: Box::new(value), children: None }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let new_node = Node::new(value);
+
+        match &mut self.children {
+            Some(children) => children.push(new_node),
+            None => {
+                self.children = Some(vec![new_node]);
+            }
+        }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children().is_empty()
+    }
+
+    pub fn children(&self) -> &[Node<T>] {
+        self.children.as_deref().unwrap_or_default()
+    }
+
+    pub fn child(&self, index: usize) -> Option<&Node<T>> {
+        self.children.as_ref()?.get(index)
+    }
+
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut Node<T>> {
+        self.children.as_mut()?.get_mut(index)
+    }
+
+    /// Inserts a leaf at position `index` among the children.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of children.
+    pub fn insert_child(&mut self, index: usize, value: T) {
+        self.insert_node(index, Node::new(value));
+    }
+
+    /// Removes the child at position `index` along with all its descendants,
+    /// and returns its value.
+    pub fn remove_child(&mut self, index: usize) -> Option<T> {
+        let mut subtree = self.detach(index)?;
+        let (value, _) = subtree.root.take()?.into_parts();
+
+        Some(*value)
+    }
+
+    /// Removes the child at position `index` and returns it, with its descendants, as a standalone tree.
+    pub fn detach(&mut self, index: usize) -> Option<Tree<T>> {
+        let children = self.children.as_mut()?;
+        if index >= children.len() {
+            return None;
+        }
+
+        let node = children.remove(index);
+        if children.is_empty() {
+            self.children = None;
+        }
+
+        Some(Tree::with_root(Some(node)))
+    }
+
+    /// Inserts the root of `subtree`, with its descendants, at position `index` among the children.
+    /// Grafting an empty tree does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of children.
+    pub fn graft(&mut self, index: usize, mut subtree: Tree<T>) {
+        if let Some(root) = subtree.root.take() {
+            self.insert_node(index, root);
+        }
+    }
+
+    /// Returns the first node of the subtree, in pre-order, whose value matches the predicate.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<&Node<T>> {
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            if predicate(&node.value) {
+                return Some(node);
+            }
+            stack.extend(node.children().iter().rev());
+        }
+
+        None
+    }
+
+    /// Returns the first node of the subtree, in pre-order, whose value matches the predicate.
+    pub fn find_mut(&mut self, mut predicate: impl FnMut(&T) -> bool) -> Option<&mut Node<T>> {
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            if predicate(&node.value) {
+                return Some(node);
+            }
+            stack.extend(node.children.iter_mut().flatten().rev());
+        }
+
+        None
+    }
+
+    fn insert_node(&mut self, index: usize, node: Node<T>) {
+        self.children.get_or_insert_with(Vec::new).insert(index, node);
+    }
+
+    /// Moves the values of the subtree into `target`, in pre-order.
+    fn populate(self, target: &mut Vec<T>) {
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            let (value, children) = node.into_parts();
+            target.push(*value);
+
+            if let Some(children) = children {
+                stack.extend(children.into_iter().rev());
+            }
+        }
+    }
+
+    fn labelled_children(&self) -> Vec<(Option<&'static str>, &Node<T>)> {
+        self.children.iter().flatten().map(|c| (None, c)).collect()
+    }
+
+    /// Folds the subtree bottom-up: `f` combines the value of each node with the results of its children,
+    /// in order, and the result of the root is returned. Nodes are visited in post-order, without recursion.
+    pub fn cata<R>(&self, mut f: impl FnMut(&T, Vec<R>) -> R) -> R {
+        let mut stack = vec![(self, false)];
+        // The results of the children of the nodes being folded, in post-order.
+        let mut results: Vec<R> = Vec::new();
+
+        while let Some((node, visited)) = stack.pop() {
+            let children = node.children();
+            if visited || children.is_empty() {
+                let folded = results.split_off(results.len() - children.len());
+                results.push(f(&node.value, folded));
+            } else {
+                stack.push((node, true));
+                stack.extend(children.iter().rev().map(|child| (child, false)));
+            }
+        }
+
+        results.pop().expect("the root is folded last")
+    }
+
+    fn size(&self) -> usize {
+        let mut result = 0;
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            result += 1;
+            stack.extend(node.children.iter().flatten());
+        }
+
+        result
+    }
+
+    /// Splits the node into its value and its children, which `Drop` forbids moving out directly.
+    fn into_parts(mut self) -> (Box<T>, Option<Vec<Node<T>>>) {
+        let children = self.children.take();
+        let node = ManuallyDrop::new(self);
+        // SAFETY: the node is never dropped, so its value is only owned by the returned box.
+        let value = unsafe { ptr::read(&node.value) };
+
+        (value, children)
+    }
+}
+
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        let mut stack = self.children.take().unwrap_or_default();
+
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.children.take().into_iter().flatten());
+        }
+    }
+}
+
+/// A step of the [`Debug`] output of a node.
+enum DebugStep<'a, T> {
+    Enter(&'a Node<T>),
+    Separator,
+    Exit,
+}
+
+/// Formats the subtree like a derived implementation would, without recursion.
+impl<T: Debug> Debug for Node<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![DebugStep::Enter(self)];
+
+        while let Some(step) = stack.pop() {
+            match step {
+                DebugStep::Enter(node) => {
+                    f.write_str("Node { value: ")?;
+                    node.value.fmt(f)?;
+                    f.write_str(", children: [")?;
+
+                    stack.push(DebugStep::Exit);
+                    for (index, child) in node.children().iter().enumerate().rev() {
+                        stack.push(DebugStep::Enter(child));
+                        if index > 0 {
+                            stack.push(DebugStep::Separator);
+                        }
+                    }
+                }
+                DebugStep::Separator => f.write_str(", ")?,
+                DebugStep::Exit => f.write_str("] }")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares the subtrees node by node, without recursion.
+impl<T: PartialEq> PartialEq for Node<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+
+        while let Some((a, b)) = stack.pop() {
+            if a.value != b.value || a.children().len() != b.children().len() {
+                return false;
+            }
+            stack.extend(a.children().iter().zip(b.children()));
+        }
+
+        true
+    }
+}
+
+impl<T: Clone> Clone for Node<T> {
+    fn clone(&self) -> Self {
+        self.fmap(T::clone)
+    }
+}
+
+/// A step of [`Node::fmap`]: mapping a node, or rebuilding it once its children
+/// have been mapped, at which point they are the last nodes of the `mapped` stack.
+enum MapStep<'a, T, U> {
+    Enter(&'a Node<T>),
+    Exit(U, Option<usize>),
+}
+
+/// A node being consumed by [`Tree::map_into`]: its mapped value, its remaining children
+/// in reverse order, and where its mapped children start on the `mapped` stack.
+struct MapIntoFrame<T, U> {
+    value: U,
+    pending: Vec<Node<T>>,
+    start: usize,
+}
+
+/// Reuses the buffer of an empty vector of nodes for nodes of another type.
+fn recycle<T, U>(vec: Vec<Node<T>>) -> Vec<Node<U>> {
+    // A node only holds pointers, so this always holds: check it anyway rather than assume it.
+    if !vec.is_empty() || Layout::new::<Node<T>>() != Layout::new::<Node<U>>() {
+        return Vec::new();
+    }
+
+    let mut vec = ManuallyDrop::new(vec);
+    // SAFETY: the buffer was allocated by a vector with the same layout of elements, and `ManuallyDrop` keeps
+    // the old vector from freeing it, so it is only owned by the new one. Its length is 0, so no `Node<T>`
+    // is ever read as a `Node<U>`.
+    unsafe { Vec::from_raw_parts(vec.as_mut_ptr() as *mut Node<U>, 0, vec.capacity()) }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tree<T> {
+    root: Option<Node<T>>,
+}
+
+impl<T> Tree<T> {
+    pub fn new(root: T) -> Self {
+        Self { root: Some(Node::new(root)) }
+    }
+
+    fn with_root(root: Option<Node<T>>) -> Self {
+        Self { root }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn root(&self) -> Option<&Node<T>> {
+        self.root.as_ref()
+    }
+
+    pub fn root_mut(&mut self) -> Option<&mut Node<T>> {
+        self.root.as_mut()
+    }
+
+    /// Returns the node found by following the child indices in `path` from the root,
+    /// so that `[]` is the root and `[1, 0]` is the first child of the second child of the root.
+    pub fn get(&self, path: &[usize]) -> Option<&Node<T>> {
+        path.iter().try_fold(self.root.as_ref()?, |node, &index| node.child(index))
+    }
+
+    /// Returns the node found by following the child indices in `path` from the root.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Node<T>> {
+        path.iter().try_fold(self.root.as_mut()?, |node, &index| node.child_mut(index))
+    }
+
+    /// Returns the first node, in pre-order, whose value matches the predicate.
+    pub fn find(&self, predicate: impl FnMut(&T) -> bool) -> Option<&Node<T>> {
+        self.root.as_ref()?.find(predicate)
+    }
+
+    /// Returns the first node, in pre-order, whose value matches the predicate.
+    pub fn find_mut(&mut self, predicate: impl FnMut(&T) -> bool) -> Option<&mut Node<T>> {
+        self.root.as_mut()?.find_mut(predicate)
+    }
+
+    /// Returns a zipper focused on the root, or `None` if the tree is empty.
+    pub fn zipper(self) -> Option<Zipper<T>> {
+        Zipper::new(self)
+    }
+
+    pub fn size(&self) -> usize {
+        self.root.as_ref().map_or(0, Node::size)
+    }
+
+    /// Returns an iterator over the values in pre-order: each node before its children.
+    pub fn iter(&self) -> PreOrder<'_, T> {
+        PreOrder::new(self.root.as_ref())
+    }
+
+    /// Returns an iterator over the values in post-order: each node after its children.
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder::new(self.root.as_ref())
+    }
+
+    /// Returns an iterator over the values level by level, from the root.
+    pub fn breadth_first(&self) -> BreadthFirst<'_, T> {
+        BreadthFirst::new(self.root.as_ref())
+    }
+
+    /// Returns an iterator over the values in pre-order, along with their depth (0 for the root).
+    pub fn with_depth(&self) -> WithDepth<'_, T> {
+        WithDepth::new(self.root.as_ref())
+    }
+
+    /// Returns an iterator over the nodes in pre-order, yielding for each one
+    /// the values on the path from the root to the node, both included.
+    pub fn paths(&self) -> Paths<'_, T> {
+        Paths::new(self.root.as_ref())
+    }
+
+    /// Consumes the tree and maps every value with `f`, in pre-order.
+    ///
+    /// Unlike [`Functor::fmap`], the values are moved into `f` rather than borrowed,
+    /// and the vectors holding the children are reused for the new tree.
+    pub fn map_into<U>(mut self, mut f: impl FnMut(T) -> U) -> Tree<U> {
+        let root = match self.root.take() {
+            None => return Tree::default(),
+            Some(root) => root,
+        };
+
+        let mut frames: Vec<MapIntoFrame<T, U>> = Vec::new();
+        let mut mapped: Vec<Node<U>> = Vec::new();
+        let mut next = Some(root);
+
+        loop {
+            if let Some(node) = next.take() {
+                let (value, children) = node.into_parts();
+                let value = f(*value);
+                match children {
+                    None => mapped.push(Node::new(value)),
+                    Some(mut children) => {
+                        children.reverse();
+                        frames.push(MapIntoFrame { value, pending: children, start: mapped.len() });
+                    }
+                }
+            }
+
+            let frame = match frames.last_mut() {
+                None => break,
+                Some(frame) => frame,
+            };
+
+            match frame.pending.pop() {
+                Some(child) => next = Some(child),
+                None => {
+                    let frame = frames.pop().unwrap();
+                    let mut children = recycle(frame.pending);
+                    children.extend(mapped.drain(frame.start..));
+                    mapped.push(Node { value: Box::new(frame.value), children: Some(children) });
+                }
+            }
+        }
+
+        Tree::with_root(mapped.pop())
+    }
+
+    /// Folds the tree bottom-up with [`Node::cata`], or returns `None` if the tree is empty.
+    pub fn cata<R>(&self, f: impl FnMut(&T, Vec<R>) -> R) -> Option<R> {
+        self.root.as_ref().map(|root| root.cata(f))
+    }
+
+    /// Renders the tree as a Graphviz DOT digraph, using `label` to display the values.
+    pub fn to_dot(&self, label: impl Fn(&T) -> String) -> String {
+        render::dot(self.root.as_ref(), Node::labelled_children, |n| label(&n.value))
+    }
+
+    /// Renders the tree as an indented ASCII diagram, using `label` to display the values.
+    pub fn to_ascii(&self, label: impl Fn(&T) -> String) -> String {
+        render::ascii(self.root.as_ref(), Node::labelled_children, |n| label(&n.value))
+    }
+}
+
+impl<T: Clone> Clone for Tree<T> {
+    fn clone(&self) -> Self {
+        Self::with_root(self.root.clone())
+    }
+}
+
+impl<T> Default for Tree<T> {
+    fn default() -> Self {
+        Self { root: None }
+    }
+}
+
+/// The first value becomes the root, the following values are added as children of the root.
+impl<T> FromIterator<T> for Tree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::default();
+        tree.extend(iter);
+        tree
+    }
+}
+
+/// Values are added as children of the root. If the tree is empty, the first value becomes the root.
+impl<T> Extend<T> for Tree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            match &mut self.root {
+                None => self.root = Some(Node::new(value)),
+                Some(root) => root.add(value),
+            }
+        }
+    }
+}
+
+impl<T> Functor<T> for Tree<T> {
+    type Functor<U> = Tree<U>;
+
+    fn fmap<U>(&self, f: impl Fn(&T) -> U) -> Self::Functor<U> {
+        Tree::with_root(self.root.as_ref().map(|root| root.fmap(f)))
+    }
+}
+
+/// Maps every node of the subtree, whatever its depth, without recursion.
+/// `f` is called on the values in pre-order.
+impl<T> Functor<T> for Node<T> {
+    type Functor<U> = Node<U>;
+
+    fn fmap<U>(&self, f: impl Fn(&T) -> U) -> Self::Functor<U> {
+        let mut stack = vec![MapStep::Enter(self)];
+        let mut mapped: Vec<Node<U>> = Vec::new();
+
+        while let Some(step) = stack.pop() {
+            match step {
+                MapStep::Enter(node) => {
+                    let value = f(&node.value);
+                    match &node.children {
+                        None => mapped.push(Node::new(value)),
+                        Some(children) => {
+                            stack.push(MapStep::Exit(value, Some(children.len())));
+                            stack.extend(children.iter().rev().map(MapStep::Enter));
+                        }
+                    }
+                }
+                MapStep::Exit(value, count) => {
+                    let children = count.map(|n| mapped.split_off(mapped.len() - n));
+                    mapped.push(Node { value: Box::new(value), children });
+                }
+            }
+        }
+
+        mapped.pop().unwrap()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Tree<T> {
+    type Item = &'a T;
+    type IntoIter = PreOrder<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> From<Tree<T>> for Vec<T> {
+    fn from(mut tree: Tree<T>) -> Self {
+        let mut result: Vec<T> = Vec::new();
+        if let Some(root) = tree.root.take() {
+            root.populate(&mut result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::Deref;
+
+    use crate::traits::functor::Functor;
+
+    use super::Tree;
+
+    #[test]
+    fn tree_constructor() {
+        let tree = Tree::new(5);
+
+        assert_eq!(tree.root().unwrap().value.as_ref(), &5);
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn default() {
+        let tree: Tree<i32> = Tree::default();
+
+        assert!(tree.is_empty());
+        assert!(tree.root().is_none());
+        assert_eq!(tree.size(), 0);
+
+        let mapped = tree.fmap(|x| x * 2);
+        assert!(mapped.is_empty());
+
+        let vec: Vec<i32> = mapped.into();
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn from_iter() {
+        let tree: Tree<i32> = (1..=4).collect();
+
+        assert_eq!(tree.size(), 4);
+        assert_eq!(tree.root().unwrap().value.as_ref(), &1);
+
+        let vec: Vec<i32> = tree.into();
+        assert_eq!(vec, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut tree = Tree::default();
+        tree.extend(vec![1, 2]);
+        tree.extend(vec![3]);
+
+        assert_eq!(tree.size(), 3);
+        assert_eq!(tree.root().unwrap().value.as_ref(), &1);
+
+        let vec: Vec<i32> = tree.into();
+        assert_eq!(vec, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn fmap() {
+        let odd = Tree::new(5).fmap(|x| { x % 2 == 0 });
+        let even = Tree::new(4).fmap(|x| { x % 2 == 0 });
+
+        assert!(!*odd.root().unwrap().value.deref());
+        assert!(*even.root().unwrap().value.deref());
+    }
+
+    #[test]
+    fn fmap_depth_2() {
+        let mut tree = Tree::new(1);
+        tree.root_mut().unwrap().add(2);
+        tree.root_mut().unwrap().add(3);
+
+        let mapped : Vec<i32> = tree.fmap(|x| { x * 2 }).into();
+
+        assert_eq!(mapped.len(), 3);
+        assert_eq!(mapped[0], 2);
+        assert_eq!(mapped[1], 4);
+        assert_eq!(mapped[2], 6);
+    }
+
+    #[test]
+    fn size_depth_2() {
+        let mut tree = Tree::new(1);
+        tree.root_mut().unwrap().add(2);
+        tree.root_mut().unwrap().add(3);
+
+        assert_eq!(tree.size(), 3);
+    }
+
+    #[test]
+    fn size_depth_3() {
+        let mut tree = Tree::new(1);
+        tree.root_mut().unwrap().add(2);
+        tree.root_mut().unwrap().add(3);
+
+        assert_eq!(tree.size(), 3);
+    }
+
+    #[test]
+    fn into_vec_depth_2() {
+        let mut tree = Tree::new(1);
+        tree.root_mut().unwrap().add(2);
+        tree.root_mut().unwrap().add(3);
+
+        let vec: Vec<i32> = tree.into();
+
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec[0], 1);
+        assert_eq!(vec[1], 2);
+        assert_eq!(vec[2], 3);
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut tree = Tree::new(1);
+        tree.root_mut().unwrap().add(2);
+        tree.root_mut().unwrap().add(3);
+
+        let expected = "digraph {
+    n0 [label=\"1\"];
+    n1 [label=\"2\"];
+    n0 -> n1;
+    n2 [label=\"3\"];
+    n0 -> n2;
+}
+";
+        assert_eq!(tree.to_dot(|v| v.to_string()), expected);
+        assert_eq!(Tree::<i32>::default().to_dot(|v| v.to_string()), "digraph {\n}\n");
+    }
+
+    #[test]
+    fn to_ascii() {
+        let tree: Tree<String> = Tree::from_outline("root\n  a\n    a1\n    a2\n  b\n    b1").unwrap();
+
+        let expected = "\
+root
+├── a
+│   ├── a1
+│   └── a2
+└── b
+    └── b1
+";
+        assert_eq!(tree.to_ascii(|v| v.to_string()), expected);
+        assert_eq!(Tree::<i32>::default().to_ascii(|v| v.to_string()), "");
+    }
+
+    #[test]
+    fn debug() {
+        let tree: Tree<i32> = Tree::from_sexpr("(1 (2 4) 3)").unwrap();
+
+        assert_eq!(
+            format!("{:?}", tree.root().unwrap()),
+            "Node { value: 1, children: [Node { value: 2, children: [Node { value: 4, children: [] }] }, \
+             Node { value: 3, children: [] }] }",
+        );
+        assert_eq!(format!("{:?}", Tree::new("a")), "Tree { root: Some(Node { value: \"a\", children: [] }) }");
+    }
+
+    /// 1
+    /// ├── 2
+    /// │   ├── 4
+    /// │   │   └── 7
+    /// │   └── 5
+    /// └── 3
+    ///     └── 6
+    pub(super) fn depth_4() -> Tree<i32> {
+        Tree::from_sexpr("(1 (2 (4 7) 5) (3 6))").unwrap()
+    }
+
+    #[test]
+    fn fmap_depth_4() {
+        let tree = depth_4();
+        let mapped = tree.fmap(|x| x * 10);
+
+        assert_eq!(mapped.size(), 7);
+        assert_eq!(mapped.to_ascii(|v| v.to_string()), tree.to_ascii(|v| (v * 10).to_string()));
+
+        let vec: Vec<i32> = mapped.into();
+        assert_eq!(vec, vec![10, 20, 40, 70, 50, 30, 60]);
+    }
+
+    #[test]
+    fn fmap_wide() {
+        let tree: Tree<i32> = (0..100).collect();
+        let vec: Vec<i32> = tree.fmap(|x| x + 1).into();
+
+        assert_eq!(vec, (1..101).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn clone_depth_4() {
+        let tree = depth_4();
+        let cloned = Tree::with_root(tree.root().cloned());
+
+        assert_eq!(cloned.to_ascii(|v| v.to_string()), tree.to_ascii(|v| v.to_string()));
+    }
+
+    #[test]
+    fn map_into() {
+        let tree = depth_4().fmap(|x| x.to_string());
+        let mapped = tree.map_into(|s| s + "!");
+
+        let vec: Vec<String> = mapped.into();
+        assert_eq!(vec, vec!["1!", "2!", "4!", "7!", "5!", "3!", "6!"]);
+        assert!(Tree::<i32>::default().map_into(|x| x).is_empty());
+    }
+
+    #[test]
+    fn map_into_reuses_children() {
+        let tree = depth_4();
+        let before = tree.root().unwrap().children.as_ref().unwrap().as_ptr() as usize;
+
+        let mapped = tree.map_into(|x| x as u8 as char);
+        let after = mapped.root().unwrap().children.as_ref().unwrap().as_ptr() as usize;
+
+        assert_eq!(before, after);
+        assert_eq!(mapped.to_ascii(|c| (*c as i32).to_string()), depth_4().to_ascii(|v| v.to_string()));
+    }
+
+    #[test]
+    fn pre_order() {
+        let tree = depth_4();
+        let values: Vec<i32> = tree.iter().copied().collect();
+
+        assert_eq!(values, vec![1, 2, 4, 7, 5, 3, 6]);
+        assert_eq!((&tree).into_iter().count(), 7);
+        assert_eq!(Tree::<i32>::default().iter().next(), None);
+    }
+
+    #[test]
+    fn post_order() {
+        let tree = depth_4();
+        let values: Vec<i32> = tree.post_order().copied().collect();
+
+        assert_eq!(values, vec![7, 4, 5, 2, 6, 3, 1]);
+        assert_eq!(Tree::<i32>::default().post_order().next(), None);
+    }
+
+    #[test]
+    fn breadth_first() {
+        let tree = depth_4();
+        let values: Vec<i32> = tree.breadth_first().copied().collect();
+
+        assert_eq!(values, vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(Tree::<i32>::default().breadth_first().next(), None);
+    }
+
+    #[test]
+    fn with_depth() {
+        let tree = depth_4();
+        let values: Vec<(usize, i32)> = tree.with_depth().map(|(d, v)| (d, *v)).collect();
+
+        assert_eq!(values, vec![(0, 1), (1, 2), (2, 4), (3, 7), (2, 5), (1, 3), (2, 6)]);
+    }
+
+    #[test]
+    fn paths() {
+        let tree = depth_4();
+        let paths: Vec<Vec<i32>> = tree.paths().map(|p| p.into_iter().copied().collect()).collect();
+
+        assert_eq!(paths, vec![
+            vec![1],
+            vec![1, 2],
+            vec![1, 2, 4],
+            vec![1, 2, 4, 7],
+            vec![1, 2, 5],
+            vec![1, 3],
+            vec![1, 3, 6],
+        ]);
+        assert_eq!(Tree::<i32>::default().paths().next(), None);
+    }
+
+    #[test]
+    fn accessors() {
+        let mut tree = depth_4();
+        let root = tree.root_mut().unwrap();
+
+        assert_eq!(root.value(), &1);
+        assert_eq!(root.children().len(), 2);
+        assert_eq!(root.child(1).unwrap().value(), &3);
+        assert!(root.child(2).is_none());
+        assert!(root.child(0).unwrap().child(1).unwrap().is_leaf());
+        assert!(!root.is_leaf());
+
+        *root.value_mut() = 10;
+        *root.child_mut(1).unwrap().value_mut() = 30;
+        assert!(root.child_mut(5).is_none());
+
+        let values: Vec<i32> = tree.iter().copied().collect();
+        assert_eq!(values, vec![10, 2, 4, 7, 5, 30, 6]);
+    }
+
+    #[test]
+    fn insert_child() {
+        let mut tree = Tree::new(1);
+        let root = tree.root_mut().unwrap();
+        root.insert_child(0, 3);
+        root.insert_child(0, 2);
+        root.insert_child(2, 4);
+
+        let values: Vec<i32> = root.children().iter().map(|c| *c.value()).collect();
+        assert_eq!(values, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn remove_child() {
+        let mut tree = depth_4();
+        let root = tree.root_mut().unwrap();
+
+        assert_eq!(root.remove_child(0), Some(2));
+        assert_eq!(root.remove_child(1), None);
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![1, 3, 6]);
+
+        let root = tree.root_mut().unwrap();
+        assert_eq!(root.remove_child(0), Some(3));
+        assert!(root.is_leaf());
+        assert_eq!(root.remove_child(0), None);
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn detach_and_graft() {
+        let mut tree = depth_4();
+
+        // Move the subtree under 2 to the end of the children of 3.
+        let subtree = tree.root_mut().unwrap().detach(0).unwrap();
+        assert_eq!(subtree.iter().copied().collect::<Vec<_>>(), vec![2, 4, 7, 5]);
+
+        let three = tree.find_mut(|v| *v == 3).unwrap();
+        three.graft(1, subtree);
+        three.graft(0, Tree::default());
+
+        let expected = "\
+1
+└── 3
+    ├── 6
+    └── 2
+        ├── 4
+        │   └── 7
+        └── 5
+";
+        assert_eq!(tree.to_ascii(|v| v.to_string()), expected);
+        assert!(tree.root_mut().unwrap().detach(1).is_none());
+    }
+
+    #[test]
+    fn find() {
+        let tree = depth_4();
+
+        let found = tree.find(|v| v % 2 == 0).unwrap();
+        assert_eq!(found.value(), &2);
+        assert_eq!(found.children().len(), 2);
+
+        assert_eq!(tree.find(|v| *v > 5).unwrap().value(), &7);
+        assert!(tree.find(|v| *v > 7).is_none());
+        assert!(Tree::<i32>::default().find(|_| true).is_none());
+    }
+
+    #[test]
+    fn find_mut() {
+        let mut tree = depth_4();
+
+        tree.find_mut(|v| *v == 5).unwrap().add(8);
+        assert!(tree.find_mut(|v| *v == 42).is_none());
+
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4, 7, 5, 8, 3, 6]);
+    }
+
+    #[test]
+    fn get() {
+        let mut tree = depth_4();
+
+        assert_eq!(tree.get(&[]).unwrap().value(), &1);
+        assert_eq!(tree.get(&[0, 0, 0]).unwrap().value(), &7);
+        assert_eq!(tree.get(&[1, 0]).unwrap().value(), &6);
+        assert!(tree.get(&[2]).is_none());
+        assert!(tree.get(&[0, 1, 0]).is_none());
+        assert!(Tree::<i32>::default().get(&[]).is_none());
+
+        *tree.get_mut(&[0, 1]).unwrap().value_mut() = 50;
+        assert!(tree.get_mut(&[1, 1]).is_none());
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4, 7, 50, 3, 6]);
+    }
+
+    #[test]
+    fn equality() {
+        let mut tree = depth_4();
+
+        assert_eq!(tree, depth_4());
+        assert_eq!(Tree::<i32>::default(), Tree::default());
+        assert_ne!(tree, Tree::default());
+
+        tree.find_mut(|v| *v == 6).unwrap().add(8);
+        assert_ne!(tree, depth_4());
+
+        // Same values in pre-order, different shapes.
+        let flat: Tree<i32> = [1, 2, 4, 7, 5, 3, 6].into_iter().collect();
+        assert_ne!(flat, depth_4());
+    }
+
+    #[test]
+    fn cata() {
+        let tree = depth_4();
+
+        let sum = tree.cata(|value, children: Vec<i32>| value + children.iter().sum::<i32>());
+        assert_eq!(sum, Some(28));
+
+        let height = tree.cata(|_, children: Vec<usize>| 1 + children.into_iter().max().unwrap_or(0));
+        assert_eq!(height, Some(4));
+
+        let leaves = tree.cata(|value, children: Vec<Vec<i32>>| match children.is_empty() {
+            true => vec![*value],
+            false => children.concat(),
+        });
+        assert_eq!(leaves, Some(vec![7, 5, 6]));
+
+        let rendered = tree.cata(|value, children: Vec<String>| match children.is_empty() {
+            true => value.to_string(),
+            false => format!("({} {})", value, children.join(" ")),
+        });
+        assert_eq!(rendered.as_deref(), Some("(1 (2 (4 7) 5) (3 6))"));
+
+        assert_eq!(Tree::<i32>::default().cata(|_, _: Vec<()>| ()), None);
+    }
+
+    #[test]
+    fn cata_call_order() {
+        let mut order = Vec::new();
+        depth_4().root().unwrap().cata(|value, _: Vec<()>| order.push(*value));
+        assert_eq!(order, vec![7, 4, 5, 2, 6, 3, 1]);
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    /// Builds a tree where every node has a single child.
+    fn deep() -> Tree<usize> {
+        let mut tree = Tree::new(0);
+        let mut node = tree.root_mut().unwrap();
+        for value in 1..STRESS {
+            node.add(value);
+            node = &mut node.children.as_mut().unwrap()[0];
+        }
+        tree
+    }
+
+    #[test]
+    fn stress_deep() {
+        let tree = deep();
+        assert_eq!(tree.size(), STRESS);
+
+        let debug = format!("{:?}", tree);
+        assert!(debug.starts_with("Tree { root: Some(Node { value: 0, children: [Node { value: 1, children: ["));
+        assert!(debug.ends_with(&format!("value: {}, children: [] }}{}) }}", STRESS - 1, "] }".repeat(STRESS - 1))));
+
+        let vec: Vec<usize> = tree.into();
+        assert_eq!(vec.len(), STRESS);
+        assert!(vec.windows(2).all(|w| w[0] + 1 == w[1]));
+    }
+
+    #[test]
+    fn stress_drop_node() {
+        let node = deep().root().unwrap().clone();
+        assert_eq!(node.size(), STRESS);
+        drop(node);
+    }
+
+    #[test]
+    fn stress_fmap() {
+        let deep = deep().fmap(|x| x * 2);
+        assert_eq!(deep.size(), STRESS);
+
+        let wide: Tree<usize> = (0..STRESS).collect();
+        let wide = wide.fmap(|x| x * 2);
+        assert_eq!(wide.size(), STRESS);
+    }
+
+    #[test]
+    fn stress_map_into() {
+        let vec: Vec<usize> = deep().map_into(|x| x + 1).into();
+        assert!(vec.iter().copied().eq(1..=STRESS));
+
+        let wide: Tree<usize> = (0..STRESS).collect();
+        assert_eq!(wide.map_into(|x| x + 1).size(), STRESS);
+    }
+
+    #[test]
+    fn stress_traversals() {
+        let tree = deep();
+
+        assert!(tree.iter().copied().eq(0..STRESS));
+        assert!(tree.post_order().copied().eq((0..STRESS).rev()));
+        assert!(tree.breadth_first().copied().eq(0..STRESS));
+        assert!(tree.with_depth().all(|(d, v)| d == *v));
+        assert_eq!(tree.paths().take(100).last().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn stress_find_and_detach() {
+        let mut tree = deep();
+
+        let last = tree.find_mut(|v| *v == STRESS - 1).unwrap();
+        last.add(STRESS);
+        assert!(tree.find(|v| *v == STRESS).unwrap().is_leaf());
+
+        let subtree = tree.root_mut().unwrap().detach(0).unwrap();
+        assert_eq!(subtree.size(), STRESS);
+        assert_eq!(tree.size(), 1);
+
+        tree.root_mut().unwrap().graft(0, subtree);
+        assert_eq!(tree.root_mut().unwrap().remove_child(0), Some(1));
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn stress_equality() {
+        let mut tree = deep();
+        assert_eq!(tree, deep());
+
+        *tree.find_mut(|v| *v == STRESS - 1).unwrap().value_mut() = 0;
+        assert_ne!(tree, deep());
+    }
+
+    #[test]
+    fn stress_cata() {
+        let height = deep().cata(|_, children: Vec<usize>| 1 + children.into_iter().max().unwrap_or(0));
+        assert_eq!(height, Some(STRESS));
+
+        let wide: Tree<usize> = (0..STRESS).collect();
+        let sum = wide.cata(|value, children: Vec<usize>| value + children.iter().sum::<usize>());
+        assert_eq!(sum, Some(STRESS * (STRESS - 1) / 2));
+    }
+
+    #[test]
+    fn stress_wide() {
+        let tree: Tree<usize> = (0..STRESS).collect();
+        assert_eq!(tree.size(), STRESS);
+
+        let vec: Vec<usize> = tree.into();
+        assert_eq!(vec.len(), STRESS);
+    }
+
+    #[test]
+    fn stress_drop() {
+        drop(deep());
+    }
+}
@@ -0,0 +1,502 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use super::{Node, Tree};
+
+/// An error found while parsing a tree. Lines and columns start at 1, columns are counted in characters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The line is not indented by a whole number of levels, or more than one level deeper than the previous one.
+    Indentation { line: usize },
+    /// A second root was found after the first one.
+    MultipleRoots { line: usize, column: usize },
+    Unexpected { line: usize, column: usize, found: char },
+    UnexpectedEnd,
+    /// A quoted value is not closed, or contains an unknown escape sequence.
+    InvalidString { line: usize, column: usize },
+    /// A value could not be parsed, with the message of the parse error.
+    InvalidValue { line: usize, column: usize, message: String },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Indentation { line } => write!(f, "invalid indentation at line {}", line),
+            ParseError::MultipleRoots { line, column } => write!(f, "second root at {}:{}", line, column),
+            ParseError::Unexpected { line, column, found } => write!(f, "unexpected {:?} at {}:{}", found, line, column),
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseError::InvalidString { line, column } => write!(f, "invalid quoted value at {}:{}", line, column),
+            ParseError::InvalidValue { line, column, message } => {
+                write!(f, "invalid value at {}:{}: {}", line, column, message)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl<T: FromStr> Tree<T>
+where
+    T::Err: Display,
+{
+    /// Parses an outline: one value per line, each child indented one level deeper than its parent.
+    ///
+    /// The first indented line sets the indentation of one level, in spaces or tabs. Blank lines are skipped.
+    /// A value that starts with a quote is read as a quoted string, as in [`Tree::from_sexpr`].
+    /// An outline without any value is the empty tree.
+    ///
+    /// ```text
+    /// root
+    ///     a
+    ///         a1
+    ///     b
+    /// ```
+    pub fn from_outline(text: &str) -> Result<Self, ParseError> {
+        let mut builder = Builder::new();
+        let mut unit: Option<&str> = None;
+        let mut offset = 0;
+
+        for (index, line) in text.split('\n').enumerate() {
+            let start = offset;
+            offset += line.len() + 1;
+
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let content = line.trim_start_matches([' ', '\t']);
+            if content.trim_end().is_empty() {
+                continue;
+            }
+
+            let indentation = &line[..line.len() - content.len()];
+            let depth = match unit {
+                _ if indentation.is_empty() => 0,
+                None => {
+                    unit = Some(indentation);
+                    1
+                }
+                Some(unit) => match indentation.len() % unit.len() {
+                    0 if indentation == unit.repeat(indentation.len() / unit.len()) => indentation.len() / unit.len(),
+                    _ => return Err(ParseError::Indentation { line: index + 1 }),
+                },
+            };
+
+            if depth > builder.depth() {
+                return Err(ParseError::Indentation { line: index + 1 });
+            }
+            if depth == 0 && !builder.is_empty() {
+                return Err(ParseError::MultipleRoots { line: index + 1, column: 1 });
+            }
+
+            let value_start = start + indentation.len();
+            let line_end = start + line.len();
+            let (value, end) = match content.starts_with('"') {
+                true => unquote(text, value_start, line_end)?,
+                false => (content.trim_end().to_owned(), value_start + content.trim_end().len()),
+            };
+            if let Some((i, found)) = text[end..line_end].char_indices().find(|(_, c)| !c.is_whitespace()) {
+                let (line, column) = location(text, end + i);
+                return Err(ParseError::Unexpected { line, column, found });
+            }
+
+            builder.push(depth, parse_value(text, value_start, &value)?);
+        }
+
+        Ok(builder.finish())
+    }
+
+    /// Parses an S-expression: a leaf is a value, and a node with children is a list made of its value
+    /// followed by its children, as in `(root (a a1) b)`.
+    ///
+    /// A value is either a run of characters without whitespace, parentheses or quotes, or a quoted string
+    /// where `\"`, `\\`, `\n`, `\r` and `\t` are escaped. An empty input is the empty tree.
+    pub fn from_sexpr(text: &str) -> Result<Self, ParseError> {
+        let mut builder = Builder::new();
+        // The number of lists whose value is read, and whether the next token must be the value of a list.
+        let mut depth = 0;
+        let mut head = false;
+        let mut offset = 0;
+
+        while let Some(found) = text[offset..].chars().next() {
+            let start = offset;
+            offset += found.len_utf8();
+
+            if found.is_whitespace() {
+                continue;
+            }
+
+            let unexpected = || {
+                let (line, column) = location(text, start);
+                ParseError::Unexpected { line, column, found }
+            };
+
+            if depth == 0 && !head && !builder.is_empty() {
+                let (line, column) = location(text, start);
+                return Err(match found {
+                    ')' => unexpected(),
+                    _ => ParseError::MultipleRoots { line, column },
+                });
+            }
+
+            match found {
+                '(' if head => return Err(unexpected()),
+                '(' => head = true,
+                ')' if head || depth == 0 => return Err(unexpected()),
+                ')' => depth -= 1,
+                _ => {
+                    let (value, end) = match found {
+                        '"' => unquote(text, start, text.len())?,
+                        _ => {
+                            let len = text[start..].find(|c: char| c.is_whitespace() || "()\"".contains(c));
+                            let end = len.map_or(text.len(), |len| start + len);
+                            (text[start..end].to_owned(), end)
+                        }
+                    };
+                    offset = end;
+
+                    builder.push(depth, parse_value(text, start, &value)?);
+                    if head {
+                        depth += 1;
+                        head = false;
+                    }
+                }
+            }
+        }
+
+        match depth > 0 || head {
+            true => Err(ParseError::UnexpectedEnd),
+            false => Ok(builder.finish()),
+        }
+    }
+}
+
+impl<T: Display> Tree<T> {
+    /// Prints the tree as an outline, indented by two spaces per level, that [`Tree::from_outline`] parses back.
+    ///
+    /// Values that would not read back as they are (empty, starting with a quote, with surrounding whitespace or
+    /// line breaks) are quoted. The tree is parsed back as it is whenever values parse back from their `Display`.
+    pub fn to_outline(&self) -> String {
+        let mut result = String::new();
+
+        for (depth, value) in self.with_depth() {
+            let value = value.to_string();
+            let bare = !value.is_empty()
+                && !value.starts_with(|c: char| c == '"' || c.is_whitespace())
+                && !value.ends_with(char::is_whitespace)
+                && !value.contains(['\n', '\r']);
+
+            result.push_str(&"  ".repeat(depth));
+            push_atom(&mut result, &value, bare);
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Prints the tree as a single-line S-expression that [`Tree::from_sexpr`] parses back.
+    ///
+    /// Values that are empty or contain whitespace, parentheses or quotes are quoted.
+    pub fn to_sexpr(&self) -> String {
+        enum Step<'a, T> {
+            Open(&'a Node<T>),
+            Close,
+        }
+
+        let mut result = String::new();
+        let mut stack: Vec<Step<T>> = self.root.iter().map(Step::Open).collect();
+
+        while let Some(step) = stack.pop() {
+            let node = match step {
+                Step::Open(node) => node,
+                Step::Close => {
+                    result.push(')');
+                    continue;
+                }
+            };
+
+            if !result.is_empty() && !result.ends_with('(') {
+                result.push(' ');
+            }
+            if !node.is_leaf() {
+                result.push('(');
+                stack.push(Step::Close);
+                stack.extend(node.children().iter().rev().map(Step::Open));
+            }
+
+            let value = node.value.to_string();
+            let bare = !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || "()\"".contains(c));
+            push_atom(&mut result, &value, bare);
+        }
+
+        result
+    }
+}
+
+/// Builds a tree from values given in pre-order along with their depth, each value
+/// being at most one level deeper than the previous one.
+struct Builder<T> {
+    /// The nodes on the path from the root to the last value, whose children are not all known yet.
+    open: Vec<Node<T>>,
+}
+
+impl<T> Builder<T> {
+    fn new() -> Self {
+        Self { open: Vec::new() }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    /// The depth of the children of the last value.
+    fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    fn push(&mut self, depth: usize, value: T) {
+        debug_assert!(depth <= self.depth() && (depth > 0 || self.is_empty()));
+        self.close(depth);
+        self.open.push(Node::new(value));
+    }
+
+    fn finish(mut self) -> Tree<T> {
+        self.close(1);
+        Tree::with_root(self.open.pop())
+    }
+
+    /// Attaches the open nodes deeper than `depth` to their parents, never closing the root.
+    fn close(&mut self, depth: usize) {
+        while self.open.len() > depth.max(1) {
+            let node = self.open.pop().expect("the stack is not empty");
+            let parent = self.open.last_mut().expect("the root is never closed");
+            parent.children.get_or_insert_with(Vec::new).push(node);
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(text: &str, start: usize, value: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    value.parse().map_err(|e: T::Err| {
+        let (line, column) = location(text, start);
+        ParseError::InvalidValue { line, column, message: e.to_string() }
+    })
+}
+
+fn push_atom(result: &mut String, value: &str, bare: bool) {
+    if bare {
+        result.push_str(value);
+        return;
+    }
+
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+}
+
+/// Reads the quoted string that starts at `start` and ends before `end`,
+/// and returns it along with the offset right after it.
+fn unquote(text: &str, start: usize, end: usize) -> Result<(String, usize), ParseError> {
+    let invalid = || {
+        let (line, column) = location(text, start);
+        ParseError::InvalidString { line, column }
+    };
+
+    let mut value = String::new();
+    let mut chars = text[start + 1..end].char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, start + 1 + i + 1)),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                _ => return Err(invalid()),
+            },
+            c => value.push(c),
+        }
+    }
+
+    Err(invalid())
+}
+
+/// Returns the line and column of the byte at `offset`.
+fn location(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_structures::tree::Tree;
+
+    use super::ParseError;
+
+    const OUTLINE: &str = "\
+1
+  2
+    4
+      7
+    5
+  3
+    6
+";
+
+    const SEXPR: &str = "(1 (2 (4 7) 5) (3 6))";
+
+    fn values<T: Clone>(tree: &Tree<T>) -> Vec<(usize, T)> {
+        tree.with_depth().map(|(depth, v)| (depth, v.clone())).collect()
+    }
+
+    fn expected() -> Vec<(usize, i32)> {
+        vec![(0, 1), (1, 2), (2, 4), (3, 7), (2, 5), (1, 3), (2, 6)]
+    }
+
+    #[test]
+    fn from_outline() {
+        let tree: Tree<i32> = Tree::from_outline(OUTLINE).unwrap();
+        assert_eq!(values(&tree), expected());
+
+        let tabs = "\r\n1\r\n\t2\r\n\t\t4\r\n\t\t\t7\r\n\n\t\t5   \r\n\t3\r\n\t\t6";
+        assert_eq!(values(&Tree::<i32>::from_outline(tabs).unwrap()), expected());
+
+        let strings: Tree<String> = Tree::from_outline("root\n    a b\n        \"quoted \\\"a1\\\"\"\n    b").unwrap();
+        let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+        assert_eq!(strings, vec!["root", "a b", "quoted \"a1\"", "b"]);
+    }
+
+    #[test]
+    fn from_sexpr() {
+        let tree: Tree<i32> = Tree::from_sexpr(SEXPR).unwrap();
+        assert_eq!(values(&tree), expected());
+
+        let spaced = "\n( 1\n  (2 (4 (7)) 5)\n  (3 6) )\n";
+        assert_eq!(values(&Tree::<i32>::from_sexpr(spaced).unwrap()), expected());
+
+        let strings: Tree<String> = Tree::from_sexpr(r#"(root "a b" ("" "\"x\"\\\n") c\d)"#).unwrap();
+        let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+        assert_eq!(strings, vec!["root", "a b", "", "\"x\"\\\n", "c\\d"]);
+
+        assert_eq!(values(&Tree::<i32>::from_sexpr("42").unwrap()), vec![(0, 42)]);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Tree::<i32>::from_outline("").unwrap().is_empty());
+        assert!(Tree::<i32>::from_outline("\n  \n").unwrap().is_empty());
+        assert!(Tree::<i32>::from_sexpr(" \n").unwrap().is_empty());
+
+        assert_eq!(Tree::<i32>::default().to_outline(), "");
+        assert_eq!(Tree::<i32>::default().to_sexpr(), "");
+    }
+
+    #[test]
+    fn print() {
+        let tree: Tree<i32> = Tree::from_sexpr(SEXPR).unwrap();
+
+        assert_eq!(tree.to_outline(), OUTLINE);
+        assert_eq!(tree.to_sexpr(), SEXPR);
+        assert_eq!(Tree::new(1).to_sexpr(), "1");
+    }
+
+    #[test]
+    fn print_quoted() {
+        let tree: Tree<String> = Tree::from_sexpr(r#"("" " a" ("(b)" "c\nd") "\"e" f\g)"#).unwrap();
+
+        assert_eq!(tree.to_sexpr(), r#"("" " a" ("(b)" "c\nd") "\"e" f\g)"#);
+        assert_eq!(tree.to_outline(), "\"\"\n  \" a\"\n  (b)\n    \"c\\nd\"\n  \"\\\"e\"\n  f\\g\n");
+    }
+
+    #[test]
+    fn round_trip() {
+        let labels = ["", " ", "a b", "(", ")", "\"", "\\", "tab\t", "line\nbreak", "\r", " padded ", "é", "plain"];
+
+        let mut tree = Tree::new("root".to_owned());
+        let root = tree.root_mut().unwrap();
+        for (i, label) in labels.iter().enumerate() {
+            root.add(label.to_string());
+            root.child_mut(i).unwrap().add(label.to_string());
+        }
+
+        assert_eq!(Tree::from_outline(&tree.to_outline()), Ok(tree.clone()));
+        assert_eq!(Tree::from_sexpr(&tree.to_sexpr()), Ok(tree.clone()));
+
+        let numbers: Tree<f64> = Tree::from_sexpr("(0.5 (-1 1e3) inf)").unwrap();
+        assert_eq!(Tree::from_outline(&numbers.to_outline()), Ok(numbers.clone()));
+        assert_eq!(Tree::from_sexpr(&numbers.to_sexpr()), Ok(numbers));
+    }
+
+    #[test]
+    fn outline_errors() {
+        let parse = Tree::<String>::from_outline;
+
+        assert_eq!(parse("  a"), Err(ParseError::Indentation { line: 1 }));
+        assert_eq!(parse("a\n  b\n      c"), Err(ParseError::Indentation { line: 3 }));
+        assert_eq!(parse("a\n  b\n   c"), Err(ParseError::Indentation { line: 3 }));
+        assert_eq!(parse("a\n  b\n\t\tc"), Err(ParseError::Indentation { line: 3 }));
+        assert_eq!(parse("a\n  b\nc"), Err(ParseError::MultipleRoots { line: 3, column: 1 }));
+        assert_eq!(parse("a\n  \"b\" c"), Err(ParseError::Unexpected { line: 2, column: 7, found: 'c' }));
+        assert_eq!(parse("a\n  \"b\nc\""), Err(ParseError::InvalidString { line: 2, column: 3 }));
+        assert_eq!(parse("a\n  \"\\x\""), Err(ParseError::InvalidString { line: 2, column: 3 }));
+
+        let error = Tree::<i32>::from_outline("1\n  2\n  x").unwrap_err();
+        assert_eq!(error, ParseError::InvalidValue { line: 3, column: 3, message: "invalid digit found in string".to_owned() });
+        assert_eq!(error.to_string(), "invalid value at 3:3: invalid digit found in string");
+    }
+
+    #[test]
+    fn sexpr_errors() {
+        let parse = Tree::<String>::from_sexpr;
+
+        assert_eq!(parse("a b"), Err(ParseError::MultipleRoots { line: 1, column: 3 }));
+        assert_eq!(parse("(a)\n(b)"), Err(ParseError::MultipleRoots { line: 2, column: 1 }));
+        assert_eq!(parse("(a))"), Err(ParseError::Unexpected { line: 1, column: 4, found: ')' }));
+        assert_eq!(parse(")"), Err(ParseError::Unexpected { line: 1, column: 1, found: ')' }));
+        assert_eq!(parse("(a ())"), Err(ParseError::Unexpected { line: 1, column: 5, found: ')' }));
+        assert_eq!(parse("((a) b)"), Err(ParseError::Unexpected { line: 1, column: 2, found: '(' }));
+        assert_eq!(parse("(a (b c)"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("("), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("(a \"b)"), Err(ParseError::InvalidString { line: 1, column: 4 }));
+
+        let error = Tree::<u8>::from_sexpr("(1 (2 300))").unwrap_err();
+        assert_eq!(error, ParseError::InvalidValue { line: 1, column: 7, message: "number too large to fit in target type".to_owned() });
+        assert_eq!(ParseError::UnexpectedEnd.to_string(), "unexpected end of input");
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    #[test]
+    fn stress_deep() {
+        let mut text: String = (0..STRESS - 1).map(|i| format!("({} ", i)).collect();
+        text += &(STRESS - 1).to_string();
+        text += &")".repeat(STRESS - 1);
+
+        let tree: Tree<usize> = Tree::from_sexpr(&text).unwrap();
+        assert_eq!(tree.size(), STRESS);
+        assert_eq!(tree.with_depth().last(), Some((STRESS - 1, &(STRESS - 1))));
+
+        assert_eq!(tree.to_sexpr(), text);
+    }
+
+    #[test]
+    fn stress_wide() {
+        let tree: Tree<usize> = (0..STRESS).collect();
+
+        let outline = tree.to_outline();
+        assert_eq!(outline.lines().count(), STRESS);
+        assert_eq!(Tree::from_outline(&outline), Ok(tree));
+    }
+}
@@ -0,0 +1,321 @@
+use super::{Node, Tree};
+
+/// A cursor over a [`Tree`] that owns the tree while it is being edited.
+///
+/// The focused node is held apart from the rest of the tree, and every level above it
+/// is kept as a breadcrumb made of the parent value and the siblings of the focus.
+/// Moving the focus and editing it only touches the focus and the closest breadcrumb,
+/// and [`Zipper::into_tree`] rebuilds the tree in O(depth) moves up.
+///
+/// Moves consume the zipper and give it back, as `Err` when the move is not possible,
+/// so that they can be chained with `and_then`.
+#[derive(Debug)]
+pub struct Zipper<T> {
+    /// Always `Some`, only taken when the tree is rebuilt.
+    focus: Option<Node<T>>,
+    path: Vec<Crumb<T>>,
+}
+
+#[derive(Debug)]
+struct Crumb<T> {
+    value: Box<T>,
+    /// The siblings before the focus, in order.
+    left: Vec<Node<T>>,
+    /// The siblings after the focus, in reverse order so that the closest one is last.
+    right: Vec<Node<T>>,
+}
+
+impl<T> Zipper<T> {
+    /// Returns a zipper focused on the root of the tree, or `None` if the tree is empty.
+    pub fn new(mut tree: Tree<T>) -> Option<Self> {
+        let root = tree.root.take()?;
+        Some(Self { focus: Some(root), path: Vec::new() })
+    }
+
+    pub fn focus(&self) -> &Node<T> {
+        self.focus.as_ref().expect("the zipper always has a focus")
+    }
+
+    pub fn focus_mut(&mut self) -> &mut Node<T> {
+        self.focus.as_mut().expect("the zipper always has a focus")
+    }
+
+    /// Applies `f` to the focused node and returns the zipper.
+    pub fn edit(mut self, f: impl FnOnce(&mut Node<T>)) -> Self {
+        f(self.focus_mut());
+        self
+    }
+
+    /// Returns the number of levels between the root and the focus.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Moves the focus to the child at position `index`.
+    pub fn down(mut self, index: usize) -> Result<Self, Self> {
+        let focus = self.focus_mut();
+        let mut children = match focus.children.take() {
+            Some(children) if index < children.len() => children,
+            children => {
+                focus.children = children;
+                return Err(self);
+            }
+        };
+
+        let mut right = children.split_off(index + 1);
+        right.reverse();
+        let child = children.pop();
+
+        let (value, _) = self.focus.take().expect("the zipper always has a focus").into_parts();
+        self.path.push(Crumb { value, left: children, right });
+        self.focus = child;
+
+        Ok(self)
+    }
+
+    /// Moves the focus to the parent, putting the focused node back among its siblings.
+    pub fn up(mut self) -> Result<Self, Self> {
+        let Some(crumb) = self.path.pop() else {
+            return Err(self);
+        };
+
+        let mut children = crumb.left;
+        children.extend(self.focus.take());
+        children.extend(crumb.right.into_iter().rev());
+        self.focus = Some(Node { value: crumb.value, children: Some(children) });
+
+        Ok(self)
+    }
+
+    /// Moves the focus to the previous sibling.
+    pub fn left(mut self) -> Result<Self, Self> {
+        let Some(crumb) = self.path.last_mut() else {
+            return Err(self);
+        };
+        let Some(sibling) = crumb.left.pop() else {
+            return Err(self);
+        };
+
+        crumb.right.extend(self.focus.replace(sibling));
+        Ok(self)
+    }
+
+    /// Moves the focus to the next sibling.
+    pub fn right(mut self) -> Result<Self, Self> {
+        let Some(crumb) = self.path.last_mut() else {
+            return Err(self);
+        };
+        let Some(sibling) = crumb.right.pop() else {
+            return Err(self);
+        };
+
+        crumb.left.extend(self.focus.replace(sibling));
+        Ok(self)
+    }
+
+    /// Moves the focus back to the root.
+    pub fn top(mut self) -> Self {
+        loop {
+            match self.up() {
+                Ok(zipper) => self = zipper,
+                Err(zipper) => return zipper,
+            }
+        }
+    }
+
+    /// Rebuilds the tree, with all the edits made through the zipper.
+    pub fn into_tree(self) -> Tree<T> {
+        let mut zipper = self.top();
+        Tree::with_root(zipper.focus.take())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_structures::tree::test::depth_4;
+    use crate::data_structures::tree::{Node, Tree};
+    use crate::traits::functor::Functor;
+    use crate::redux::store::Store;
+
+    use super::Zipper;
+
+    fn values(tree: &Tree<i32>) -> Vec<i32> {
+        tree.iter().copied().collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Tree::<i32>::default().zipper().is_none());
+        assert!(Zipper::new(Tree::<i32>::default()).is_none());
+    }
+
+    #[test]
+    fn unchanged() {
+        let tree = depth_4().zipper().unwrap().into_tree();
+        assert_eq!(values(&tree), vec![1, 2, 4, 7, 5, 3, 6]);
+    }
+
+    #[test]
+    fn navigation() {
+        let zipper = depth_4().zipper().unwrap();
+        assert!(zipper.is_root());
+        assert_eq!(zipper.focus().value(), &1);
+
+        let zipper = zipper.down(0).unwrap().down(0).unwrap().down(0).unwrap();
+        assert_eq!(zipper.focus().value(), &7);
+        assert_eq!(zipper.depth(), 3);
+
+        let zipper = zipper.up().unwrap().right().unwrap();
+        assert_eq!(zipper.focus().value(), &5);
+
+        let zipper = zipper.left().unwrap();
+        assert_eq!(zipper.focus().value(), &4);
+
+        let zipper = zipper.top().down(1).unwrap().down(0).unwrap();
+        assert_eq!(zipper.focus().value(), &6);
+        assert_eq!(zipper.depth(), 2);
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let zipper = depth_4().zipper().unwrap();
+
+        let zipper = zipper.up().unwrap_err();
+        let zipper = zipper.left().unwrap_err();
+        let zipper = zipper.right().unwrap_err();
+        let zipper = zipper.down(2).unwrap_err();
+
+        let zipper = zipper.down(0).unwrap().left().unwrap_err();
+        let zipper = zipper.right().unwrap().right().unwrap_err();
+        let zipper = zipper.down(1).unwrap_err().down(0).unwrap().down(0).unwrap_err();
+        assert_eq!(zipper.focus().value(), &6);
+
+        // Failed moves leave the tree untouched.
+        assert_eq!(values(&zipper.into_tree()), vec![1, 2, 4, 7, 5, 3, 6]);
+    }
+
+    #[test]
+    fn edit() {
+        let tree = depth_4().zipper().unwrap()
+            .down(0).unwrap()
+            .right().unwrap()
+            .edit(|node| *node.value_mut() = 30)
+            .down(0).unwrap()
+            .edit(|node| {
+                node.add(8);
+                node.add(9);
+            })
+            .top()
+            .down(0).unwrap()
+            .down(1).unwrap()
+            .edit(|node| node.insert_child(0, 50))
+            .into_tree();
+
+        let expected = "\
+1
+├── 2
+│   ├── 4
+│   │   └── 7
+│   └── 5
+│       └── 50
+└── 30
+    └── 6
+        ├── 8
+        └── 9
+";
+        assert_eq!(tree.to_ascii(|v| v.to_string()), expected);
+    }
+
+    #[test]
+    fn edit_in_place() {
+        let mut zipper = depth_4().zipper().unwrap().down(0).unwrap();
+
+        let subtree = zipper.focus_mut().detach(0).unwrap();
+        *zipper.focus_mut().value_mut() = 20;
+
+        let mut zipper = zipper.right().unwrap();
+        zipper.focus_mut().graft(1, subtree);
+
+        assert_eq!(values(&zipper.into_tree()), vec![1, 20, 5, 3, 6, 4, 7]);
+    }
+
+    #[derive(Clone, Default)]
+    struct State {
+        config: Tree<String>,
+    }
+
+    enum Action {
+        /// Renames the node found by following the child indices from the root.
+        Rename(Vec<usize>, String),
+    }
+
+    fn mutate(state: State, action: Action) -> State {
+        match action {
+            Action::Rename(path, name) => {
+                let Some(mut zipper) = state.config.zipper() else {
+                    return State::default();
+                };
+                for index in path {
+                    zipper = zipper.down(index).unwrap_or_else(|z| z);
+                }
+
+                State { config: zipper.edit(|node| *node.value_mut() = name).into_tree() }
+            }
+        }
+    }
+
+    #[test]
+    fn redux_state() {
+        let config = depth_4().fmap(|v| v.to_string());
+        let mut store = Store::new(State { config }, &mutate);
+
+        let saved = store.get_state().clone();
+
+        store.dispatch(Action::Rename(vec![0, 1], "five".to_owned()));
+        store.dispatch(Action::Rename(vec![1], "three".to_owned()));
+
+        let config: Vec<&str> = store.get_state().config.iter().map(String::as_str).collect();
+        assert_eq!(config, vec!["1", "2", "4", "7", "five", "three", "6"]);
+
+        let saved: Vec<&str> = saved.config.iter().map(String::as_str).collect();
+        assert_eq!(saved, vec!["1", "2", "4", "7", "5", "3", "6"]);
+    }
+
+    #[test]
+    fn stress_deep() {
+        const STRESS: usize = 1_000_000;
+
+        let mut root = Node::new(0);
+        let mut node = &mut root;
+        for i in 1..STRESS {
+            node.add(i);
+            node = node.child_mut(0).unwrap();
+        }
+        let tree = Tree::with_root(Some(root));
+
+        // Dropping a zipper at the root of a deep tree.
+        drop(tree.clone().zipper().unwrap());
+
+        let mut zipper = tree.zipper().unwrap();
+        for _ in 1..STRESS {
+            zipper = zipper.down(0).unwrap();
+        }
+        let zipper = zipper.down(0).unwrap_err();
+        assert_eq!(zipper.depth(), STRESS - 1);
+
+        let zipper = zipper.edit(|node| node.add(STRESS));
+        let tree = zipper.into_tree();
+        assert_eq!(tree.size(), STRESS + 1);
+
+        // Dropping a zipper in the middle of a deep tree.
+        let mut zipper = tree.zipper().unwrap();
+        for _ in 0..STRESS / 2 {
+            zipper = zipper.down(0).unwrap();
+        }
+        drop(zipper);
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::{btree_map, BTreeMap};
+use std::fmt::Debug;
+use std::mem;
+
+pub use radix::{RadixIter, RadixTrie};
+
+mod radix;
+
+/// A prefix tree mapping byte strings to values, with one node per byte of the keys.
+///
+/// Keys are anything that can be seen as bytes, such as `&str`, `String` or `Vec<u8>`.
+/// Children are kept sorted, so keys are iterated in lexicographic order of their bytes.
+/// See [`RadixTrie`] for a variant that stores a whole run of bytes in each node.
+pub struct Trie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+struct Node<V> {
+    value: Option<V>,
+    children: BTreeMap<u8, Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Self { value: None, children: BTreeMap::new() }
+    }
+}
+
+impl<V> Trie<V> {
+    pub fn new() -> Self {
+        Self { root: Node::new(), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value for the key, and returns the previous value if there was one.
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for &byte in key.as_ref() {
+            node = node.children.entry(byte).or_insert_with(Node::new);
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&V> {
+        self.node(key.as_ref())?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut V> {
+        self.node_mut(key.as_ref())?.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: impl AsRef<[u8]>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the key and returns its value, along with the nodes that no longer lead to any value.
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> Option<V> {
+        let key = key.as_ref();
+
+        // The depth of the deepest node on the path that leads to other keys, if the path is cut below it.
+        let mut keep = 0;
+        let mut node = &self.root;
+        for (depth, byte) in key.iter().enumerate() {
+            if node.value.is_some() || node.children.len() > 1 {
+                keep = depth;
+            }
+            node = node.children.get(byte)?;
+        }
+        node.value.as_ref()?;
+        self.len -= 1;
+
+        if key.is_empty() || !node.children.is_empty() {
+            return self.node_mut(key).and_then(|node| node.value.take());
+        }
+
+        // No other key goes through the nodes below `keep`: they only form a chain down to the value.
+        let parent = self.node_mut(&key[..keep]).expect("the path exists");
+        let mut node = parent.children.remove(&key[keep]).expect("the path exists");
+        while let Some((_, child)) = node.children.pop_first() {
+            node = child;
+        }
+        node.value
+    }
+
+    /// Returns the longest key that is a prefix of `key`, as its length in bytes, along with its value.
+    pub fn longest_prefix(&self, key: impl AsRef<[u8]>) -> Option<(usize, &V)> {
+        let mut node = &self.root;
+        let mut longest = node.value.as_ref().map(|value| (0, value));
+
+        for (depth, byte) in key.as_ref().iter().enumerate() {
+            match node.children.get(byte) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = &node.value {
+                longest = Some((depth + 1, value));
+            }
+        }
+
+        longest
+    }
+
+    /// Returns an iterator over the keys and values, in lexicographic order of the keys.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter::new(Vec::new(), Some(&self.root))
+    }
+
+    /// Returns an iterator over the keys that start with `prefix` and their values,
+    /// in lexicographic order of the keys.
+    pub fn with_prefix(&self, prefix: impl AsRef<[u8]>) -> Iter<'_, V> {
+        let prefix = prefix.as_ref();
+        Iter::new(prefix.to_vec(), self.node(prefix))
+    }
+
+    fn node(&self, key: &[u8]) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for byte in key {
+            node = node.children.get(byte)?;
+        }
+        Some(node)
+    }
+
+    fn node_mut(&mut self, key: &[u8]) -> Option<&mut Node<V>> {
+        let mut node = &mut self.root;
+        for byte in key {
+            node = node.children.get_mut(byte)?;
+        }
+        Some(node)
+    }
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Drop for Trie<V> {
+    fn drop(&mut self) {
+        let mut stack = vec![mem::take(&mut self.root.children)];
+
+        while let Some(children) = stack.pop() {
+            stack.extend(children.into_values().map(|mut node| mem::take(&mut node.children)));
+        }
+    }
+}
+
+impl<V: Debug> Debug for Trie<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for Trie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<K: AsRef<[u8]>, V> Extend<(K, V)> for Trie<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a Trie<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the keys and values below a node, in pre-order, which is the lexicographic order of the keys.
+pub struct Iter<'a, V> {
+    /// The key of the last node reached.
+    key: Vec<u8>,
+    /// The value of the node where the iteration starts, which comes first.
+    first: Option<&'a V>,
+    /// The children left to visit, for every node from the start to the last node reached.
+    stack: Vec<btree_map::Iter<'a, u8, Node<V>>>,
+}
+
+impl<'a, V> Iter<'a, V> {
+    fn new(key: Vec<u8>, start: Option<&'a Node<V>>) -> Self {
+        Self {
+            key,
+            first: start.and_then(|node| node.value.as_ref()),
+            stack: start.into_iter().map(|node| node.children.iter()).collect(),
+        }
+    }
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.first.take() {
+            return Some((self.key.clone(), value));
+        }
+
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some((&byte, node)) => {
+                    self.key.push(byte);
+                    self.stack.push(node.children.iter());
+                    if let Some(value) = &node.value {
+                        return Some((self.key.clone(), value));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    if !self.stack.is_empty() {
+                        self.key.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Trie;
+
+    fn words() -> Trie<usize> {
+        ["tea", "ten", "to", "inn", "in", "i", "team", "tо"].iter().enumerate().map(|(i, w)| (w, i)).collect()
+    }
+
+    fn keys<V>(iter: impl Iterator<Item = (Vec<u8>, V)>) -> Vec<String> {
+        iter.map(|(key, _)| String::from_utf8(key).unwrap()).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let trie: Trie<i32> = Trie::default();
+
+        assert!(trie.is_empty());
+        assert_eq!(trie.len(), 0);
+        assert_eq!(trie.get("a"), None);
+        assert_eq!(trie.longest_prefix("abc"), None);
+        assert_eq!(trie.iter().next(), None);
+    }
+
+    #[test]
+    fn insert_get() {
+        let mut trie = words();
+        assert_eq!(trie.len(), 8);
+
+        assert_eq!(trie.get("tea"), Some(&0));
+        assert_eq!(trie.get("i"), Some(&5));
+        assert_eq!(trie.get(b"team"), Some(&6));
+        assert_eq!(trie.get(String::from("tо")), Some(&7));
+        assert_eq!(trie.get("te"), None);
+        assert_eq!(trie.get("teams"), None);
+        assert_eq!(trie.get(""), None);
+        assert!(trie.contains_key("inn"));
+        assert!(!trie.contains_key("x"));
+
+        assert_eq!(trie.insert("tea", 10), Some(0));
+        assert_eq!(trie.insert("", 11), None);
+        assert_eq!(trie.len(), 9);
+        assert_eq!(trie.get(""), Some(&11));
+
+        *trie.get_mut("ten").unwrap() += 100;
+        assert_eq!(trie.get("ten"), Some(&101));
+        assert_eq!(trie.get_mut("te"), None);
+    }
+
+    #[test]
+    fn remove() {
+        let mut trie = words();
+
+        assert_eq!(trie.remove("te"), None);
+        assert_eq!(trie.remove("teams"), None);
+        assert_eq!(trie.remove(""), None);
+
+        // A key with longer keys below it.
+        assert_eq!(trie.remove("tea"), Some(0));
+        assert_eq!(trie.get("team"), Some(&6));
+
+        // A key at the end of a branch shared with other keys.
+        assert_eq!(trie.remove("team"), Some(6));
+        assert_eq!(trie.get("ten"), Some(&1));
+        assert!(!trie.root.children[&b't'].children[&b'e'].children.contains_key(&b'a'));
+
+        // A key below another key.
+        assert_eq!(trie.remove("inn"), Some(3));
+        assert_eq!(trie.get("in"), Some(&4));
+        assert!(trie.root.children[&b'i'].children[&b'n'].children.is_empty());
+
+        assert_eq!(trie.len(), 5);
+        for key in ["ten", "to", "in", "i", "tо"] {
+            assert!(trie.remove(key).is_some());
+        }
+        assert!(trie.is_empty());
+        assert!(trie.root.children.is_empty());
+
+        trie.insert("", 1);
+        assert_eq!(trie.remove(""), Some(1));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn longest_prefix() {
+        let mut trie = words();
+
+        assert_eq!(trie.longest_prefix("teammate"), Some((4, &6)));
+        assert_eq!(trie.longest_prefix("tea"), Some((3, &0)));
+        assert_eq!(trie.longest_prefix("innate"), Some((3, &3)));
+        assert_eq!(trie.longest_prefix("ink"), Some((2, &4)));
+        assert_eq!(trie.longest_prefix("tab"), None);
+        assert_eq!(trie.longest_prefix(""), None);
+
+        trie.insert("", 42);
+        assert_eq!(trie.longest_prefix("tab"), Some((0, &42)));
+    }
+
+    #[test]
+    fn iter() {
+        let trie = words();
+
+        assert_eq!(keys(trie.iter()), vec!["i", "in", "inn", "tea", "team", "ten", "to", "tо"]);
+        assert_eq!(trie.iter().map(|(_, v)| *v).collect::<Vec<_>>(), vec![5, 4, 3, 0, 6, 1, 2, 7]);
+        assert_eq!(keys((&trie).into_iter()).len(), trie.len());
+        assert_eq!(format!("{:?}", Trie::from_iter([("ab", 1)])), "{[97, 98]: 1}");
+    }
+
+    #[test]
+    fn with_prefix() {
+        let trie = words();
+
+        assert_eq!(keys(trie.with_prefix("te")), vec!["tea", "team", "ten"]);
+        assert_eq!(keys(trie.with_prefix("tea")), vec!["tea", "team"]);
+        assert_eq!(keys(trie.with_prefix("in")), vec!["in", "inn"]);
+        assert_eq!(keys(trie.with_prefix("")), keys(trie.iter()));
+        assert_eq!(keys(trie.with_prefix("x")), Vec::<String>::new());
+        assert_eq!(keys(trie.with_prefix("teams")), Vec::<String>::new());
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    #[test]
+    fn stress_long_key() {
+        let key = vec![b'a'; STRESS];
+        let mut trie = Trie::new();
+        trie.insert(&key, 1);
+        trie.insert(&key[..STRESS / 2], 2);
+
+        assert_eq!(trie.longest_prefix(&key[..STRESS - 1]), Some((STRESS / 2, &2)));
+        assert_eq!(trie.iter().map(|(key, _)| key.len()).collect::<Vec<_>>(), vec![STRESS / 2, STRESS]);
+
+        assert_eq!(trie.remove(&key), Some(1));
+        assert_eq!(trie.len(), 1);
+
+        trie.insert(&key, 3);
+        drop(trie);
+    }
+
+    #[test]
+    fn stress_many_keys() {
+        let trie: Trie<usize> = (0..STRESS).map(|i| (i.to_string(), i)).collect();
+
+        assert_eq!(trie.len(), STRESS);
+        assert_eq!(trie.get("123456"), Some(&123456));
+        assert_eq!(trie.with_prefix("99999").count(), 11);
+        assert!(trie.iter().map(|(key, _)| key).collect::<Vec<_>>().windows(2).all(|w| w[0] < w[1]));
+    }
+}
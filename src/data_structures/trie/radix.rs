@@ -0,0 +1,504 @@
+use std::collections::{btree_map, BTreeMap};
+use std::fmt::Debug;
+use std::mem;
+
+/// A compact prefix tree mapping byte strings to values, where every chain of nodes without values
+/// or branches is merged into a single node labelled with the bytes of the chain.
+///
+/// It has the same interface as [`Trie`](super::Trie), but only holds one node per key and per
+/// branching point, instead of one node per byte.
+pub struct RadixTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+struct Node<V> {
+    /// The bytes between the parent and this node. Only the root has an empty label.
+    label: Vec<u8>,
+    value: Option<V>,
+    /// The children, by the first byte of their label.
+    children: BTreeMap<u8, Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new(label: &[u8], value: Option<V>) -> Self {
+        Self { label: label.to_vec(), value, children: BTreeMap::new() }
+    }
+
+    /// Merges the node with its only child, when the node has no value.
+    fn merge_child(&mut self) {
+        if self.value.is_some() || self.children.len() != 1 {
+            return;
+        }
+
+        let (_, child) = self.children.pop_first().expect("the node has a child");
+        self.label.extend_from_slice(&child.label);
+        self.value = child.value;
+        self.children = child.children;
+    }
+}
+
+impl<V> RadixTrie<V> {
+    pub fn new() -> Self {
+        Self { root: Node::new(&[], None), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value for the key, and returns the previous value if there was one.
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, value: V) -> Option<V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+
+        while let Some(&first) = key.first() {
+            let child = match node.children.entry(first) {
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(Node::new(key, Some(value)));
+                    self.len += 1;
+                    return None;
+                }
+                btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            };
+
+            let common = common_prefix(&child.label, key);
+            if common < child.label.len() {
+                // Split the child where the key leaves its label.
+                let mut rest = Node::new(&child.label[common..], child.value.take());
+                rest.children = mem::take(&mut child.children);
+                child.label.truncate(common);
+                child.children.insert(rest.label[0], rest);
+            }
+
+            key = &key[common..];
+            node = child;
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&V> {
+        self.node(key.as_ref())?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+
+        while let Some(first) = key.first() {
+            node = node.children.get_mut(first).filter(|child| key.starts_with(&child.label))?;
+            key = &key[node.label.len()..];
+        }
+
+        node.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: impl AsRef<[u8]>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the key and returns its value, merging the nodes left with a single child and no value.
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> Option<V> {
+        let mut key = key.as_ref();
+        // The first bytes of the labels on the path from the root to the node of the key.
+        let mut path = Vec::new();
+        let mut node = &self.root;
+
+        while let Some(&first) = key.first() {
+            node = node.children.get(&first).filter(|child| key.starts_with(&child.label))?;
+            key = &key[node.label.len()..];
+            path.push(first);
+        }
+        node.value.as_ref()?;
+        self.len -= 1;
+
+        let Some((&last, parents)) = path.split_last() else {
+            return self.root.value.take();
+        };
+
+        let parent = self.node_mut(parents);
+        let node = parent.children.get_mut(&last).expect("the path exists");
+        let value = node.value.take();
+
+        if node.children.is_empty() {
+            parent.children.remove(&last);
+            // The root keeps an empty label, even with a single child.
+            if !parents.is_empty() {
+                parent.merge_child();
+            }
+        } else {
+            node.merge_child();
+        }
+
+        value
+    }
+
+    /// Returns the longest key that is a prefix of `key`, as its length in bytes, along with its value.
+    pub fn longest_prefix(&self, key: impl AsRef<[u8]>) -> Option<(usize, &V)> {
+        let key = key.as_ref();
+        let mut node = &self.root;
+        let mut depth = 0;
+        let mut longest = node.value.as_ref().map(|value| (0, value));
+
+        while let Some(child) = key.get(depth).and_then(|first| node.children.get(first)) {
+            if !key[depth..].starts_with(&child.label) {
+                break;
+            }
+
+            node = child;
+            depth += child.label.len();
+            if let Some(value) = &node.value {
+                longest = Some((depth, value));
+            }
+        }
+
+        longest
+    }
+
+    /// Returns an iterator over the keys and values, in lexicographic order of the keys.
+    pub fn iter(&self) -> RadixIter<'_, V> {
+        RadixIter::new(Vec::new(), Some(&self.root))
+    }
+
+    /// Returns an iterator over the keys that start with `prefix` and their values,
+    /// in lexicographic order of the keys.
+    pub fn with_prefix(&self, prefix: impl AsRef<[u8]>) -> RadixIter<'_, V> {
+        let mut prefix = prefix.as_ref();
+        let mut key = Vec::new();
+        let mut node = &self.root;
+
+        // The prefix may end in the middle of a label, in which case the whole label belongs to the keys.
+        while let Some(first) = prefix.first() {
+            match node.children.get(first) {
+                Some(child) if child.label.starts_with(prefix) || prefix.starts_with(&child.label) => {
+                    key.extend_from_slice(&child.label);
+                    prefix = &prefix[prefix.len().min(child.label.len())..];
+                    node = child;
+                }
+                _ => return RadixIter::new(key, None),
+            }
+        }
+
+        RadixIter::new(key, Some(node))
+    }
+
+    fn node(&self, mut key: &[u8]) -> Option<&Node<V>> {
+        let mut node = &self.root;
+
+        while let Some(first) = key.first() {
+            node = node.children.get(first).filter(|child| key.starts_with(&child.label))?;
+            key = &key[node.label.len()..];
+        }
+
+        Some(node)
+    }
+
+    /// Returns the node reached by following the children by the first bytes of their labels.
+    fn node_mut(&mut self, path: &[u8]) -> &mut Node<V> {
+        let mut node = &mut self.root;
+        for first in path {
+            node = node.children.get_mut(first).expect("the path exists");
+        }
+        node
+    }
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+impl<V> Default for RadixTrie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Drop for RadixTrie<V> {
+    fn drop(&mut self) {
+        let mut stack = vec![mem::take(&mut self.root.children)];
+
+        while let Some(children) = stack.pop() {
+            stack.extend(children.into_values().map(|mut node| mem::take(&mut node.children)));
+        }
+    }
+}
+
+impl<V: Debug> Debug for RadixTrie<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for RadixTrie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<K: AsRef<[u8]>, V> Extend<(K, V)> for RadixTrie<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a RadixTrie<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = RadixIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the keys and values below a node, in pre-order, which is the lexicographic order of the keys.
+pub struct RadixIter<'a, V> {
+    /// The key of the last node reached.
+    key: Vec<u8>,
+    /// The value of the node where the iteration starts, which comes first.
+    first: Option<&'a V>,
+    /// The children left to visit and the length of the key, for every node from the start to the last node reached.
+    stack: Vec<(btree_map::Values<'a, u8, Node<V>>, usize)>,
+}
+
+impl<'a, V> RadixIter<'a, V> {
+    fn new(key: Vec<u8>, start: Option<&'a Node<V>>) -> Self {
+        let len = key.len();
+        Self {
+            key,
+            first: start.and_then(|node| node.value.as_ref()),
+            stack: start.into_iter().map(|node| (node.children.values(), len)).collect(),
+        }
+    }
+}
+
+impl<'a, V> Iterator for RadixIter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.first.take() {
+            return Some((self.key.clone(), value));
+        }
+
+        loop {
+            let (children, len) = self.stack.last_mut()?;
+            match children.next() {
+                Some(node) => {
+                    self.key.truncate(*len);
+                    self.key.extend_from_slice(&node.label);
+                    self.stack.push((node.children.values(), self.key.len()));
+                    if let Some(value) = &node.value {
+                        return Some((self.key.clone(), value));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RadixTrie;
+
+    fn words() -> RadixTrie<usize> {
+        ["tea", "ten", "to", "inn", "in", "i", "team", "tо"].iter().enumerate().map(|(i, w)| (w, i)).collect()
+    }
+
+    fn keys<V>(iter: impl Iterator<Item = (Vec<u8>, V)>) -> Vec<String> {
+        iter.map(|(key, _)| String::from_utf8(key).unwrap()).collect()
+    }
+
+    /// The labels of the nodes in pre-order, with their depth.
+    fn shape<V>(trie: &RadixTrie<V>) -> Vec<(usize, String)> {
+        let mut result = Vec::new();
+        let mut stack = vec![(0, &trie.root)];
+
+        while let Some((depth, node)) = stack.pop() {
+            result.push((depth, String::from_utf8_lossy(&node.label).into_owned()));
+            stack.extend(node.children.values().rev().map(|child| (depth + 1, child)));
+        }
+
+        result
+    }
+
+    #[test]
+    fn empty() {
+        let trie: RadixTrie<i32> = RadixTrie::default();
+
+        assert!(trie.is_empty());
+        assert_eq!(trie.get("a"), None);
+        assert_eq!(trie.longest_prefix("abc"), None);
+        assert_eq!(trie.iter().next(), None);
+        assert_eq!(trie.with_prefix("a").next(), None);
+    }
+
+    #[test]
+    fn insert_get() {
+        let mut trie = words();
+        assert_eq!(trie.len(), 8);
+
+        assert_eq!(trie.get("tea"), Some(&0));
+        assert_eq!(trie.get("i"), Some(&5));
+        assert_eq!(trie.get(b"team"), Some(&6));
+        assert_eq!(trie.get("te"), None);
+        assert_eq!(trie.get("teams"), None);
+        assert_eq!(trie.get("tx"), None);
+        assert_eq!(trie.get(""), None);
+        assert!(trie.contains_key("inn"));
+
+        assert_eq!(trie.insert("tea", 10), Some(0));
+        assert_eq!(trie.insert("", 11), None);
+        assert_eq!(trie.len(), 9);
+        assert_eq!(trie.get(""), Some(&11));
+
+        *trie.get_mut("ten").unwrap() += 100;
+        assert_eq!(trie.get("ten"), Some(&101));
+        assert_eq!(trie.get_mut("te"), None);
+        assert_eq!(trie.get_mut("tex"), None);
+    }
+
+    #[test]
+    fn compact() {
+        let trie: RadixTrie<i32> = [("romane", 1), ("romanus", 2), ("romulus", 3), ("rubens", 4)].into_iter().collect();
+
+        let expected = vec![(0, ""), (1, "r"), (2, "om"), (3, "an"), (4, "e"), (4, "us"), (3, "ulus"), (2, "ubens")];
+        let expected: Vec<(usize, String)> = expected.into_iter().map(|(d, l)| (d, l.to_owned())).collect();
+        assert_eq!(shape(&trie), expected);
+    }
+
+    #[test]
+    fn remove() {
+        let mut trie: RadixTrie<i32> = [("romane", 1), ("romanus", 2), ("romulus", 3), ("rubens", 4)].into_iter().collect();
+
+        assert_eq!(trie.remove("roman"), None);
+        assert_eq!(trie.remove("romanes"), None);
+        assert_eq!(trie.remove("r"), None);
+
+        // The parent is left with a single child, and merged with it.
+        assert_eq!(trie.remove("romane"), Some(1));
+        let labels: Vec<String> = shape(&trie).into_iter().map(|(_, label)| label).collect();
+        assert_eq!(labels, vec!["", "r", "om", "anus", "ulus", "ubens"]);
+
+        // A node with children loses its value, and is merged with its only child.
+        trie.insert("rom", 5);
+        trie.insert("romu", 6);
+        assert_eq!(trie.remove("romu"), Some(6));
+        assert_eq!(trie.remove("rom"), Some(5));
+        let labels: Vec<String> = shape(&trie).into_iter().map(|(_, label)| label).collect();
+        assert_eq!(labels, vec!["", "r", "om", "anus", "ulus", "ubens"]);
+
+        assert_eq!(trie.remove("rubens"), Some(4));
+        assert_eq!(trie.remove("romulus"), Some(3));
+        let labels: Vec<String> = shape(&trie).into_iter().map(|(_, label)| label).collect();
+        assert_eq!(labels, vec!["", "romanus"]);
+
+        assert_eq!(trie.remove("romanus"), Some(2));
+        assert!(trie.is_empty());
+        assert!(trie.root.children.is_empty());
+
+        trie.insert("", 1);
+        assert_eq!(trie.remove(""), Some(1));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn longest_prefix() {
+        let mut trie = words();
+
+        assert_eq!(trie.longest_prefix("teammate"), Some((4, &6)));
+        assert_eq!(trie.longest_prefix("tea"), Some((3, &0)));
+        assert_eq!(trie.longest_prefix("innate"), Some((3, &3)));
+        assert_eq!(trie.longest_prefix("ink"), Some((2, &4)));
+        assert_eq!(trie.longest_prefix("tab"), None);
+        assert_eq!(trie.longest_prefix(""), None);
+
+        trie.insert("", 42);
+        assert_eq!(trie.longest_prefix("tab"), Some((0, &42)));
+    }
+
+    #[test]
+    fn iter() {
+        let trie = words();
+
+        assert_eq!(keys(trie.iter()), vec!["i", "in", "inn", "tea", "team", "ten", "to", "tо"]);
+        assert_eq!(trie.iter().map(|(_, v)| *v).collect::<Vec<_>>(), vec![5, 4, 3, 0, 6, 1, 2, 7]);
+        assert_eq!(keys((&trie).into_iter()).len(), trie.len());
+        assert_eq!(format!("{:?}", RadixTrie::from_iter([("ab", 1)])), "{[97, 98]: 1}");
+    }
+
+    #[test]
+    fn with_prefix() {
+        let trie: RadixTrie<i32> = [("romane", 1), ("romanus", 2), ("romulus", 3), ("rubens", 4)].into_iter().collect();
+
+        assert_eq!(keys(trie.with_prefix("rom")), vec!["romane", "romanus", "romulus"]);
+        assert_eq!(keys(trie.with_prefix("roma")), vec!["romane", "romanus"]);
+        assert_eq!(keys(trie.with_prefix("romanu")), vec!["romanus"]);
+        assert_eq!(keys(trie.with_prefix("romanus")), vec!["romanus"]);
+        assert_eq!(keys(trie.with_prefix("")), keys(trie.iter()));
+        assert_eq!(keys(trie.with_prefix("rob")), Vec::<String>::new());
+        assert_eq!(keys(trie.with_prefix("romanuss")), Vec::<String>::new());
+        assert_eq!(keys(trie.with_prefix("x")), Vec::<String>::new());
+    }
+
+    const STRESS: usize = 1_000_000;
+
+    #[test]
+    fn stress_nested_keys() {
+        // Every prefix of a long key is a key: one node per byte, as in a plain trie. The depth of a radix trie
+        // only grows with the square root of the total length of the keys, so it is kept smaller here.
+        const NESTED: usize = 2_000;
+
+        let key = vec![b'a'; NESTED];
+        let mut trie: RadixTrie<usize> = (0..=NESTED).map(|len| (&key[..len], len)).collect();
+
+        assert_eq!(trie.len(), NESTED + 1);
+        assert_eq!(trie.longest_prefix(b"aaaaaaaaaab"), Some((10, &10)));
+        assert_eq!(trie.remove(&key[..NESTED / 2]), Some(NESTED / 2));
+        assert_eq!(trie.get(&key), Some(&NESTED));
+        assert_eq!(trie.iter().count(), NESTED);
+    }
+
+    #[test]
+    fn stress_long_key() {
+        let key = vec![b'a'; STRESS];
+        let mut trie = RadixTrie::new();
+        trie.insert(&key, 1);
+        trie.insert(&key[..STRESS / 2], 2);
+
+        assert_eq!(shape(&trie).len(), 3);
+        assert_eq!(trie.longest_prefix(&key[..STRESS - 1]), Some((STRESS / 2, &2)));
+        assert_eq!(trie.remove(&key[..STRESS / 2]), Some(2));
+        assert_eq!(shape(&trie).len(), 2);
+    }
+
+    #[test]
+    fn stress_many_keys() {
+        let mut trie: RadixTrie<usize> = (0..STRESS).map(|i| (i.to_string(), i)).collect();
+
+        assert_eq!(trie.len(), STRESS);
+        assert_eq!(trie.get("123456"), Some(&123456));
+        assert_eq!(trie.with_prefix("99999").count(), 11);
+        assert!(trie.iter().map(|(key, _)| key).collect::<Vec<_>>().windows(2).all(|w| w[0] < w[1]));
+
+        for i in (0..STRESS).step_by(2) {
+            assert_eq!(trie.remove(i.to_string()), Some(i));
+        }
+        assert_eq!(trie.len(), STRESS / 2);
+        assert!(trie.iter().all(|(_, value)| value % 2 == 1));
+    }
+}